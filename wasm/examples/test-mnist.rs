@@ -1,4 +1,4 @@
-use ndarray::ArrayView1;
+use ndarray::{CowArray, Ix1};
 use mnist::dataset::{Dataset, MNIST};
 
 fn main() {
@@ -26,7 +26,7 @@ fn print_data_at(dataset: &Dataset, i: usize) {
     );
 }
 
-fn print_image(data: ArrayView1<f64>) {
+fn print_image(data: CowArray<f64, Ix1>) {
     let data = data.to_shape((28, 28)).unwrap();
     for i in 0..28usize {
         for j in 0..28usize {
@@ -36,6 +36,6 @@ fn print_image(data: ArrayView1<f64>) {
                 _ => print!("⬛"),
             }
         }
-        println!("");
+        println!();
     }
 }
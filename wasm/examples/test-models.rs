@@ -12,7 +12,7 @@ fn test_model<M: Model + ?Sized>(
 ) {
     let start = Instant::now();
     for i in 0..max_iteration {
-        let err_rate = model.step(&training);
+        let err_rate = model.step(training);
         println!("{}: {}", i, err_rate);
     }
     let duration = start.elapsed();
@@ -22,11 +22,22 @@ fn test_model<M: Model + ?Sized>(
     );
 
     let start = Instant::now();
-    let err_rate = model.evaluate(&testing);
+    let err_rate = model.evaluate(testing);
     let duration = start.elapsed();
     println!("Time elapsed in testing: {:?}", duration);
 
     println!("Testing Result: {}", err_rate);
+
+    let predictions = model.predict_batch(testing);
+    let n_correct = predictions
+        .iter()
+        .zip(testing.labels())
+        .filter(|(p, l)| p == l)
+        .count();
+    println!(
+        "Accuracy: {}",
+        n_correct as f64 / testing.n_observations() as f64
+    );
 }
 
 fn print_image(data: ArrayView1<f64>) {
@@ -39,21 +50,21 @@ fn print_image(data: ArrayView1<f64>) {
                 _ => print!("⬛"),
             }
         }
-        println!("");
+        println!();
     }
 }
 
 fn test_kmeans(training: &Dataset, testing: &Dataset, max_iteration: usize) {
     let mut model = KMeans::new(10, training.data_size());
-    test_model(&mut model, &training, &testing, max_iteration);
+    test_model(&mut model, training, testing, max_iteration);
 }
 
 fn test_knn(training: &Dataset, testing: &Dataset, max_iteration: usize) {
     let mut model = KNearestNeighbors::new(10);
-    // test_model(&mut model, &training, &testing, max_iteration);
+    // test_model(&mut model, training, testing, max_iteration);
 
     let start = Instant::now();
-    let err_rate = model.step(&training);
+    let err_rate = model.step(training);
     println!("[knn] Training: {}", err_rate);
     let duration = start.elapsed();
     println!(
@@ -62,24 +73,24 @@ fn test_knn(training: &Dataset, testing: &Dataset, max_iteration: usize) {
     );
 
     let start = Instant::now();
-    let err_rate = model.evaluate(&testing);
+    let err_rate = model.evaluate(testing);
     let duration = start.elapsed();
     println!("Time elapsed in evaluate: {:?}", duration);
     println!("Testing Result: {}", err_rate);
 
-    let observation = testing.observations().row(0);
+    let observation = testing.observation(0);
     let start = Instant::now();
     let prediction = model.calculate_prediction(&observation);
     let duration = start.elapsed();
     println!("Prediction: {:?}", duration);
 
-    print_image(observation);
+    print_image(observation.view());
     println!("{:?}", prediction);
 }
 
 fn test_perceptron(training: &Dataset, testing: &Dataset, max_iteration: usize) {
     let mut model = Perceptron::new(0.01, training.data_size(), training.target_size());
-    test_model(&mut model, &training, &testing, max_iteration);
+    test_model(&mut model, training, testing, max_iteration);
 }
 
 fn main() {
@@ -88,7 +99,10 @@ fn main() {
 
     let max_iteration = 100;
 
-    // test_kmeans(&training, &testing, max_iteration);
-    // test_knn(&training, &testing, max_iteration);
-    test_perceptron(&training, &testing, max_iteration);
+    // cargo run --example test-models [kmeans|knn|perceptron]
+    match std::env::args().nth(1).as_deref() {
+        Some("kmeans") => test_kmeans(&training, &testing, max_iteration),
+        Some("knn") => test_knn(&training, &testing, max_iteration),
+        _ => test_perceptron(&training, &testing, max_iteration),
+    }
 }
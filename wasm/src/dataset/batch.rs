@@ -0,0 +1,178 @@
+use super::{Dataset, DatasetError};
+use ndarray::{Array1, Array2, Axis};
+use ndarray_rand::rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+/// Endless stream of mini-batches over a dataset, one epoch after another
+///
+/// Each batch is a `DatasetView` of `batch_size` indices into the dataset, the
+/// observations are only copied when a model reads them.
+#[wasm_bindgen]
+pub struct Batches {
+    batch_size: usize,
+    shuffle: bool,
+    drop_last: bool,
+    rng: StdRng,
+    /// Order of the observations for the current epoch
+    indices: Vec<usize>,
+    position: usize,
+    epoch: usize,
+}
+
+#[wasm_bindgen]
+impl Batches {
+    /// - shuffle: reorder the observations at the start of every epoch
+    /// - drop_last: skip the last batch of an epoch if it is smaller than batch_size
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        n_observations: usize,
+        batch_size: usize,
+        shuffle: bool,
+        drop_last: bool,
+        seed: u64,
+    ) -> Self {
+        let mut batches = Self {
+            batch_size: batch_size.max(1),
+            shuffle,
+            drop_last,
+            rng: StdRng::seed_from_u64(seed),
+            indices: (0..n_observations).collect(),
+            position: 0,
+            epoch: 0,
+        };
+        if shuffle {
+            batches.indices.shuffle(&mut batches.rng);
+        }
+        batches
+    }
+
+    /// JS can't borrow the dataset, so the batch is copied into a `Dataset`
+    #[wasm_bindgen(js_name = next_batch)]
+    pub fn js_next_batch(&mut self, dataset: &Dataset) -> Result<Dataset, JsError> {
+        Ok(self.next_batch(dataset)?.to_dataset())
+    }
+
+    /// Number of completed passes over the dataset
+    pub fn epoch(&self) -> usize {
+        self.epoch
+    }
+
+    /// Number of batches in one epoch
+    pub fn n_batches(&self) -> usize {
+        let n = self.indices.len();
+        if self.drop_last {
+            n / self.batch_size
+        } else {
+            n.div_ceil(self.batch_size)
+        }
+    }
+}
+
+impl Batches {
+    /// Next mini-batch of `dataset`, which must have the n_observations given
+    /// to the constructor
+    pub fn next_batch<'a>(
+        &mut self,
+        dataset: &'a Dataset,
+    ) -> Result<DatasetView<'a>, DatasetError> {
+        if dataset.n_observations() != self.indices.len() {
+            return Err(DatasetError::ObservationCount {
+                expected: self.indices.len(),
+                actual: dataset.n_observations(),
+            });
+        }
+        if self.n_batches() == 0 {
+            return Err(DatasetError::NoBatch {
+                n_observations: self.indices.len(),
+                batch_size: self.batch_size,
+            });
+        }
+
+        let range = match self.next_range() {
+            Some(range) => range,
+            None => {
+                self.next_epoch();
+                // an epoch has at least one batch
+                self.next_range().unwrap()
+            }
+        };
+        Ok(DatasetView {
+            dataset,
+            indices: self.indices[range].to_vec(),
+        })
+    }
+
+    /// Range of `indices` for the next batch of this epoch
+    fn next_range(&mut self) -> Option<std::ops::Range<usize>> {
+        let start = self.position;
+        let end = (start + self.batch_size).min(self.indices.len());
+        if start >= end || (self.drop_last && end - start < self.batch_size) {
+            return None;
+        }
+        self.position = end;
+        Some(start..end)
+    }
+
+    fn next_epoch(&mut self) {
+        self.epoch += 1;
+        self.position = 0;
+        if self.shuffle {
+            self.indices.shuffle(&mut self.rng);
+        }
+    }
+}
+
+/// Observations of a dataset at some indices, e.g. a mini-batch
+pub struct DatasetView<'a> {
+    dataset: &'a Dataset,
+    indices: Vec<usize>,
+}
+
+impl<'a> DatasetView<'a> {
+    pub fn n_observations(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Indices of the observations in the dataset
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Shape: (n_observations, data_size), only the rows of the view are
+    /// converted to f64
+    pub fn observations(&self) -> Array2<f64> {
+        let mut observations = Array2::zeros((self.indices.len(), self.dataset.data_size()));
+        for (mut row, &i) in observations.rows_mut().into_iter().zip(&self.indices) {
+            row.assign(&self.dataset.stored_observations().row(i));
+        }
+        observations
+    }
+
+    pub fn targets(&self) -> Array2<f64> {
+        self.dataset.targets().select(Axis(0), &self.indices)
+    }
+
+    pub fn labels(&self) -> Array1<usize> {
+        self.dataset.labels().select(Axis(0), &self.indices)
+    }
+
+    /// Copy of the observations in their stored element type
+    pub fn to_dataset(&self) -> Dataset {
+        self.dataset.select(&self.indices)
+    }
+}
+
+impl Dataset {
+    /// Mini-batches covering the dataset once
+    pub fn batches(
+        &self,
+        batch_size: usize,
+        shuffle: bool,
+        drop_last: bool,
+        seed: u64,
+    ) -> impl Iterator<Item = DatasetView<'_>> + '_ {
+        let mut batches = Batches::new(self.n_observations(), batch_size, shuffle, drop_last, seed);
+        // the batches are built for this dataset, so selecting can't fail
+        (0..batches.n_batches()).map(move |_| batches.next_batch(self).unwrap())
+    }
+}
@@ -0,0 +1,255 @@
+//! Plain text datasets with one observation per line, e.g. the Kaggle
+//! `label,pixel0,...,pixel783` format
+use super::{Dataset, DatasetError, Observations};
+use ndarray::{Array1, Array2};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    io::{BufRead, Write},
+};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct CsvOptions {
+    /// First line contains column names
+    pub has_header: bool,
+    /// Index of the label column, every other column is a pixel
+    pub label_column: usize,
+    /// Observation = pixel * scale when reading, pixel = observation / scale when writing
+    pub scale: f64,
+    /// Number of classes used to build the targets
+    pub n_classes: usize,
+}
+
+#[wasm_bindgen]
+impl CsvOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new(has_header: bool, label_column: usize, scale: f64, n_classes: usize) -> Self {
+        Self {
+            has_header,
+            label_column,
+            scale,
+            n_classes,
+        }
+    }
+}
+
+impl Default for CsvOptions {
+    /// Kaggle digit recognizer format
+    fn default() -> Self {
+        Self::new(true, 0, 1.0, 10)
+    }
+}
+
+#[wasm_bindgen]
+impl Dataset {
+    /// Decode a CSV dataset
+    pub fn from_csv(csv: &str, options: &CsvOptions) -> Result<Dataset, JsError> {
+        Ok(Self::read_csv(csv.as_bytes(), options)?)
+    }
+
+    /// Encode the dataset as CSV
+    pub fn to_csv(&self, options: &CsvOptions) -> Result<String, JsError> {
+        let mut buf = Vec::new();
+        self.write_csv(&mut buf, options)?;
+        Ok(String::from_utf8(buf)?)
+    }
+}
+
+impl Dataset {
+    /// Observations are stored as u8 if every pixel is an integer in 0..=255
+    /// and `scale` is 1, as f32 otherwise
+    pub fn read_csv<R: BufRead>(r: R, options: &CsvOptions) -> Result<Dataset, CsvError> {
+        let mut pixels = if options.scale == 1.0 {
+            Pixels::U8(Vec::new())
+        } else {
+            Pixels::F32(Vec::new())
+        };
+        let mut labels = Vec::new();
+        let mut n_columns = None;
+
+        for (i, line) in r.lines().enumerate() {
+            let line = line.map_err(CsvError::Io)?;
+            if (i == 0 && options.has_header) || line.trim().is_empty() {
+                continue;
+            }
+            let line_number = i + 1;
+
+            let columns: Vec<&str> = line.trim().split(',').collect();
+            let expected = *n_columns.get_or_insert(columns.len());
+            if options.label_column >= expected {
+                return Err(CsvError::LabelColumn {
+                    label_column: options.label_column,
+                    n_columns: expected,
+                });
+            }
+            if columns.len() != expected {
+                return Err(CsvError::Columns {
+                    line: line_number,
+                    expected,
+                    actual: columns.len(),
+                });
+            }
+
+            for (j, column) in columns.iter().enumerate() {
+                let parse_error = || CsvError::Parse {
+                    line: line_number,
+                    column: j,
+                };
+                if j == options.label_column {
+                    let label: usize = column.trim().parse().map_err(|_| parse_error())?;
+                    if label >= options.n_classes {
+                        return Err(CsvError::Label {
+                            line: line_number,
+                            label,
+                            n_classes: options.n_classes,
+                        });
+                    }
+                    labels.push(label);
+                } else {
+                    let pixel: f64 = column.trim().parse().map_err(|_| parse_error())?;
+                    pixels.push(pixel * options.scale);
+                }
+            }
+        }
+
+        let n_observations = labels.len();
+        let data_size = n_columns.map_or(0, |n| n - 1);
+        let shape = (n_observations, data_size);
+        let observations: Observations = match pixels {
+            Pixels::U8(pixels) => Array2::from_shape_vec(shape, pixels).unwrap().into(),
+            Pixels::F32(pixels) => Array2::from_shape_vec(shape, pixels).unwrap().into(),
+        };
+        Dataset::from_labels(observations, Array1::from_vec(labels), options.n_classes)
+            .map_err(CsvError::Dataset)
+    }
+
+    /// Observations are converted to f64 one at a time
+    pub fn write_csv<W: Write>(&self, mut w: W, options: &CsvOptions) -> Result<(), CsvError> {
+        let n_columns = 1 + self.data_size();
+        if options.label_column >= n_columns {
+            return Err(CsvError::LabelColumn {
+                label_column: options.label_column,
+                n_columns,
+            });
+        }
+
+        if options.has_header {
+            let header = (0..self.data_size()).map(|i| format!("pixel{}", i));
+            write_row(&mut w, options.label_column, "label".to_string(), header)?;
+        }
+
+        for (observation, label) in self.observation_label_iter() {
+            let pixels = observation.iter().map(|v| (v / options.scale).to_string());
+            write_row(&mut w, options.label_column, label.to_string(), pixels)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Pixels read so far, kept as u8 while every pixel is an integer in 0..=255
+/// and as f32 once one isn't
+enum Pixels {
+    U8(Vec<u8>),
+    F32(Vec<f32>),
+}
+
+impl Pixels {
+    fn push(&mut self, pixel: f64) {
+        match self {
+            Pixels::U8(pixels) if pixel.fract() == 0.0 && (0.0..=255.0).contains(&pixel) => {
+                pixels.push(pixel as u8)
+            }
+            Pixels::U8(pixels) => {
+                let mut converted: Vec<f32> = pixels.iter().map(|&v| v as f32).collect();
+                converted.push(pixel as f32);
+                *self = Pixels::F32(converted);
+            }
+            Pixels::F32(pixels) => pixels.push(pixel as f32),
+        }
+    }
+}
+
+fn write_row<W: Write>(
+    w: &mut W,
+    label_column: usize,
+    label: String,
+    pixels: impl Iterator<Item = String>,
+) -> Result<(), CsvError> {
+    let mut row: Vec<String> = pixels.collect();
+    row.insert(label_column, label);
+    writeln!(w, "{}", row.join(",")).map_err(CsvError::Io)
+}
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(std::io::Error),
+    Parse {
+        line: usize,
+        column: usize,
+    },
+    Columns {
+        line: usize,
+        expected: usize,
+        actual: usize,
+    },
+    Label {
+        line: usize,
+        label: usize,
+        n_classes: usize,
+    },
+    /// Label column is past the last column
+    LabelColumn {
+        label_column: usize,
+        n_columns: usize,
+    },
+    /// Decoded observations and labels don't make a dataset
+    Dataset(DatasetError),
+}
+impl Error for CsvError {}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            CsvError::Io(e) => e.fmt(f),
+            CsvError::Parse { line, column } => {
+                write!(f, "Line {}: column {} is not a number", line, column)
+            }
+            CsvError::Columns {
+                line,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "Line {}: expected {} columns but got {}",
+                    line, expected, actual
+                )
+            }
+            CsvError::Label {
+                line,
+                label,
+                n_classes,
+            } => {
+                write!(
+                    f,
+                    "Line {}: label {} is out of range 0..{}",
+                    line, label, n_classes
+                )
+            }
+            CsvError::LabelColumn {
+                label_column,
+                n_columns,
+            } => {
+                write!(
+                    f,
+                    "Label column {} is out of range 0..{}",
+                    label_column, n_columns
+                )
+            }
+            CsvError::Dataset(e) => e.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use super::{
+    mnist::{dataset_from_gz_idx, IdxFormat},
+    Dataset, IdxError,
+};
+use std::io::Read;
+use wasm_bindgen::prelude::*;
+
+const DIGITS_FORMAT: IdxFormat = IdxFormat {
+    class_names: &["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"],
+    label_offset: 0,
+    transposed: true,
+};
+
+/// Upper and lower case are merged into the same class
+const LETTERS_FORMAT: IdxFormat = IdxFormat {
+    class_names: &[
+        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R",
+        "S", "T", "U", "V", "W", "X", "Y", "Z",
+    ],
+    label_offset: 1,
+    transposed: true,
+};
+
+/// Extended MNIST, handwritten digits and letters from NIST Special Database 19
+/// - https://www.nist.gov/itl/products-and-services/emnist-dataset
+///
+/// Images are stored transposed and letters are labelled from 1, both are
+/// converted to match MNIST.
+#[wasm_bindgen]
+pub struct EMNIST;
+
+#[wasm_bindgen]
+impl EMNIST {
+    /// Decode EMNIST Digits dataset, n_observations is taken from the file
+    pub fn digits_from_gz(
+        images_idx3_ubyte_gz: &[u8],
+        labels_idx1_ubyte_gz: &[u8],
+    ) -> Result<Dataset, JsError> {
+        Ok(Self::read_digits_gz(
+            images_idx3_ubyte_gz,
+            labels_idx1_ubyte_gz,
+        )?)
+    }
+
+    /// Decode EMNIST Letters dataset, n_observations is taken from the file
+    pub fn letters_from_gz(
+        images_idx3_ubyte_gz: &[u8],
+        labels_idx1_ubyte_gz: &[u8],
+    ) -> Result<Dataset, JsError> {
+        Ok(Self::read_letters_gz(
+            images_idx3_ubyte_gz,
+            labels_idx1_ubyte_gz,
+        )?)
+    }
+}
+
+impl EMNIST {
+    /// Decode EMNIST Digits dataset from gzip compressed readers
+    pub fn read_digits_gz<R: Read>(images: R, labels: R) -> Result<Dataset, IdxError> {
+        dataset_from_gz_idx(images, labels, None, &DIGITS_FORMAT)
+    }
+
+    /// Decode EMNIST Letters dataset from gzip compressed readers
+    pub fn read_letters_gz<R: Read>(images: R, labels: R) -> Result<Dataset, IdxError> {
+        dataset_from_gz_idx(images, labels, None, &LETTERS_FORMAT)
+    }
+}
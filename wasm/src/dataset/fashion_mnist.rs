@@ -0,0 +1,46 @@
+use super::{
+    mnist::{dataset_from_gz_idx, IdxFormat},
+    Dataset, IdxError,
+};
+use std::io::Read;
+use wasm_bindgen::prelude::*;
+
+const FASHION_MNIST_FORMAT: IdxFormat = IdxFormat {
+    class_names: &[
+        "T-shirt/top",
+        "Trouser",
+        "Pullover",
+        "Dress",
+        "Coat",
+        "Sandal",
+        "Shirt",
+        "Sneaker",
+        "Bag",
+        "Ankle boot",
+    ],
+    label_offset: 0,
+    transposed: false,
+};
+
+/// Zalando article images in the MNIST format
+/// - https://github.com/zalandoresearch/fashion-mnist
+#[wasm_bindgen]
+pub struct FashionMNIST;
+
+#[wasm_bindgen]
+impl FashionMNIST {
+    /// Decode Fashion-MNIST dataset, n_observations is taken from the file
+    pub fn from_gz(
+        images_idx3_ubyte_gz: &[u8],
+        labels_idx1_ubyte_gz: &[u8],
+    ) -> Result<Dataset, JsError> {
+        Ok(Self::read_gz(images_idx3_ubyte_gz, labels_idx1_ubyte_gz)?)
+    }
+}
+
+impl FashionMNIST {
+    /// Decode Fashion-MNIST dataset from gzip compressed readers
+    pub fn read_gz<R: Read>(images: R, labels: R) -> Result<Dataset, IdxError> {
+        dataset_from_gz_idx(images, labels, None, &FASHION_MNIST_FORMAT)
+    }
+}
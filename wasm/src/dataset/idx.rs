@@ -0,0 +1,334 @@
+//! Decoder and encoder for the IDX file format
+//!
+//! - http://yann.lecun.com/exdb/mnist/ (bottom of the page)
+//!
+//! magic number: [0, 0, data type, number of dimensions]
+//! dimensions:   one big-endian u32 per dimension
+//! data:         big-endian elements in row-major (C) order
+use super::DatasetError;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use ndarray::{ArrayBase, ArrayD, Data, Dimension, ErrorKind, IxDyn, ShapeError};
+use std::{
+    convert::TryInto,
+    error::Error,
+    fmt::{Display, Formatter},
+    io::{Read, Write},
+};
+
+/// Element type, stored in the third byte of the magic number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdxType {
+    U8,
+    I8,
+    I16,
+    I32,
+    F32,
+    F64,
+}
+
+impl IdxType {
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x08 => Some(IdxType::U8),
+            0x09 => Some(IdxType::I8),
+            0x0B => Some(IdxType::I16),
+            0x0C => Some(IdxType::I32),
+            0x0D => Some(IdxType::F32),
+            0x0E => Some(IdxType::F64),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> u8 {
+        match self {
+            IdxType::U8 => 0x08,
+            IdxType::I8 => 0x09,
+            IdxType::I16 => 0x0B,
+            IdxType::I32 => 0x0C,
+            IdxType::F32 => 0x0D,
+            IdxType::F64 => 0x0E,
+        }
+    }
+
+    /// Number of bytes used by one element
+    pub fn size(self) -> usize {
+        match self {
+            IdxType::U8 | IdxType::I8 => 1,
+            IdxType::I16 => 2,
+            IdxType::I32 | IdxType::F32 => 4,
+            IdxType::F64 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdxHeader {
+    pub data_type: IdxType,
+    /// Size of each dimension, outermost first
+    pub dims: Vec<usize>,
+}
+
+impl IdxHeader {
+    /// Number of elements described by the dimensions, `None` if it
+    /// overflows a usize
+    pub fn n_elements(&self) -> Option<usize> {
+        self.dims
+            .iter()
+            .try_fold(1usize, |n, &dim| n.checked_mul(dim))
+    }
+
+    /// Number of bytes of the data following the header
+    fn data_len(&self) -> Result<usize, IdxError> {
+        self.n_elements()
+            .and_then(|n| n.checked_mul(self.data_type.size()))
+            .ok_or_else(overflow)
+    }
+}
+
+fn overflow() -> IdxError {
+    IdxError::Shape(ShapeError::from_kind(ErrorKind::Overflow))
+}
+
+/// Element type an IDX payload can be decoded into and encoded from
+///
+/// Every IDX data type is exactly representable as f64, so elements are
+/// converted through it.
+pub trait IdxElement: Copy + 'static {
+    fn from_f64(v: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_idx_element {
+    ($($t:ty),*) => {
+        $(
+            impl IdxElement for $t {
+                fn from_f64(v: f64) -> Self {
+                    v as $t
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_idx_element!(u8, i8, i16, i32, f32, f64, usize);
+
+/// Decode a gzip compressed IDX file
+pub fn read_gz_idx<A, R>(r: R) -> Result<(IdxHeader, ArrayD<A>), IdxError>
+where
+    A: IdxElement,
+    R: Read,
+{
+    read_idx(GzDecoder::new(r))
+}
+
+/// Decode an IDX file, the shape of the array is taken from the header
+pub fn read_idx<A, R>(mut r: R) -> Result<(IdxHeader, ArrayD<A>), IdxError>
+where
+    A: IdxElement,
+    R: Read,
+{
+    let header = read_idx_header(&mut r)?;
+    let arr = read_idx_data(r, &header)?;
+    Ok((header, arr))
+}
+
+/// Decode the data following a header read with `read_idx_header`
+pub fn read_idx_data<A, R>(mut r: R, header: &IdxHeader) -> Result<ArrayD<A>, IdxError>
+where
+    A: IdxElement,
+    R: Read,
+{
+    let expected = header.data_len()?;
+    let mut buf = Vec::new();
+    r.read_to_end(&mut buf).map_err(IdxError::Io)?;
+
+    if buf.len() < expected {
+        return Err(IdxError::Truncated {
+            expected,
+            actual: buf.len(),
+        });
+    } else if buf.len() > expected {
+        return Err(IdxError::Trailing {
+            expected,
+            actual: buf.len(),
+        });
+    }
+
+    let data = decode(header.data_type, &buf);
+    ArrayD::from_shape_vec(IxDyn(&header.dims), data).map_err(IdxError::Shape)
+}
+
+/// Decode the magic number and dimensions
+pub fn read_idx_header<R: Read>(r: &mut R) -> Result<IdxHeader, IdxError> {
+    let mut magic = [0; 4];
+    r.read_exact(&mut magic).map_err(IdxError::Io)?;
+    if magic[0] != 0 || magic[1] != 0 {
+        return Err(IdxError::Magic { actual: magic });
+    }
+    let data_type = IdxType::from_code(magic[2]).ok_or(IdxError::DataType(magic[2]))?;
+
+    let mut dims = Vec::with_capacity(magic[3] as usize);
+    let mut size = [0; 4];
+    for _ in 0..magic[3] {
+        r.read_exact(&mut size).map_err(IdxError::Io)?;
+        dims.push(u32::from_be_bytes(size) as usize);
+    }
+
+    Ok(IdxHeader { data_type, dims })
+}
+
+fn decode<A: IdxElement>(data_type: IdxType, buf: &[u8]) -> Vec<A> {
+    macro_rules! decode_as {
+        ($t:ty) => {
+            buf.chunks_exact(std::mem::size_of::<$t>())
+                .map(|b| A::from_f64(<$t>::from_be_bytes(b.try_into().unwrap()) as f64))
+                .collect()
+        };
+    }
+
+    match data_type {
+        IdxType::U8 => buf.iter().map(|&v| A::from_f64(v as f64)).collect(),
+        IdxType::I8 => decode_as!(i8),
+        IdxType::I16 => decode_as!(i16),
+        IdxType::I32 => decode_as!(i32),
+        IdxType::F32 => decode_as!(f32),
+        IdxType::F64 => decode_as!(f64),
+    }
+}
+
+/// Encode an array as a gzip compressed IDX file
+pub fn write_gz_idx<A, S, D, W>(
+    w: W,
+    data_type: IdxType,
+    arr: &ArrayBase<S, D>,
+) -> Result<(), IdxError>
+where
+    A: IdxElement,
+    S: Data<Elem = A>,
+    D: Dimension,
+    W: Write,
+{
+    let mut encoder = GzEncoder::new(w, Compression::default());
+    write_idx(&mut encoder, data_type, arr)?;
+    encoder.finish().map_err(IdxError::Io)?;
+    Ok(())
+}
+
+/// Encode an array as an IDX file, elements are stored as `data_type`
+///
+/// Values are rounded when `data_type` is an integer type.
+pub fn write_idx<A, S, D, W>(
+    mut w: W,
+    data_type: IdxType,
+    arr: &ArrayBase<S, D>,
+) -> Result<(), IdxError>
+where
+    A: IdxElement,
+    S: Data<Elem = A>,
+    D: Dimension,
+    W: Write,
+{
+    let n_dims: u8 = arr.ndim().try_into().map_err(|_| overflow())?;
+    let mut buf = Vec::with_capacity(4 * (1 + arr.ndim()) + data_type.size() * arr.len());
+    buf.extend_from_slice(&[0, 0, data_type.code(), n_dims]);
+    for &dim in arr.shape() {
+        let dim: u32 = dim.try_into().map_err(|_| overflow())?;
+        buf.extend_from_slice(&dim.to_be_bytes());
+    }
+
+    encode(data_type, arr.iter().map(|v| v.to_f64()), &mut buf);
+
+    w.write_all(&buf).map_err(IdxError::Io)
+}
+
+fn encode(data_type: IdxType, values: impl Iterator<Item = f64>, buf: &mut Vec<u8>) {
+    macro_rules! encode_as {
+        ($t:ty, $f:expr) => {
+            values.for_each(|v| buf.extend_from_slice(&($f(v) as $t).to_be_bytes()))
+        };
+    }
+
+    match data_type {
+        IdxType::U8 => encode_as!(u8, f64::round),
+        IdxType::I8 => encode_as!(i8, f64::round),
+        IdxType::I16 => encode_as!(i16, f64::round),
+        IdxType::I32 => encode_as!(i32, f64::round),
+        IdxType::F32 => encode_as!(f32, std::convert::identity),
+        IdxType::F64 => encode_as!(f64, std::convert::identity),
+    }
+}
+
+#[derive(Debug)]
+pub enum IdxError {
+    Io(std::io::Error),
+    Shape(ndarray::ShapeError),
+    Magic {
+        actual: [u8; 4],
+    },
+    DataType(u8),
+    /// Header dimensions differ from the expected shape
+    CountMismatch {
+        expected: Vec<usize>,
+        actual: Vec<usize>,
+    },
+    /// Payload is shorter than the header describes (in bytes)
+    Truncated {
+        expected: usize,
+        actual: usize,
+    },
+    /// Payload is longer than the header describes (in bytes)
+    Trailing {
+        expected: usize,
+        actual: usize,
+    },
+    /// Label isn't an integer within the number of classes
+    Label {
+        label: f64,
+        n_classes: usize,
+    },
+    /// Decoded images and labels don't make a dataset
+    Dataset(DatasetError),
+}
+impl Error for IdxError {}
+
+impl Display for IdxError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            IdxError::Io(e) => e.fmt(f),
+            IdxError::Shape(e) => e.fmt(f),
+            IdxError::Magic { actual } => {
+                write!(
+                    f,
+                    "Expected magic number to start with [0, 0] but got {:?}",
+                    &actual[..2]
+                )
+            }
+            IdxError::DataType(code) => write!(f, "Unknown IDX data type 0x{:02X}", code),
+            IdxError::CountMismatch { expected, actual } => {
+                write!(f, "Expected dimensions {:?} but got {:?}", expected, actual)
+            }
+            IdxError::Truncated { expected, actual } => {
+                write!(
+                    f,
+                    "Data truncated, expected {} bytes but got {}",
+                    expected, actual
+                )
+            }
+            IdxError::Trailing { expected, actual } => {
+                write!(
+                    f,
+                    "Unexpected trailing data, expected {} bytes but got {}",
+                    expected, actual
+                )
+            }
+            IdxError::Label { label, n_classes } => {
+                write!(f, "Label {} is out of range 0..{}", label, n_classes)
+            }
+            IdxError::Dataset(e) => e.fmt(f),
+        }
+    }
+}
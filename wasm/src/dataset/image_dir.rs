@@ -0,0 +1,198 @@
+//! Labelled images sorted into one folder per class, e.g. `0/`, `1/`, ... `9/`
+use super::{Dataset, DatasetError};
+use crate::preprocess::{grayscale_to_observation, luma, over_white, IMAGE_SIZE};
+use ndarray::{Array1, Array2};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    fs::{self, File},
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+impl Dataset {
+    /// Load `root/<label>/*.png` and `root/<label>/*.pgm`
+    ///
+    /// Images are expected to have dark ink on a light background like the
+    /// canvas, they go through the same preprocessing before being inverted to
+    /// match MNIST. Entries that aren't a label folder or an image are skipped.
+    pub fn from_image_dir<P: AsRef<Path>>(
+        root: P,
+        n_classes: usize,
+    ) -> Result<Dataset, ImageError> {
+        let mut observations = Vec::new();
+        let mut labels = Vec::new();
+
+        for (label, dir) in label_dirs(root.as_ref())? {
+            if label >= n_classes {
+                return Err(ImageError::Label { label, n_classes });
+            }
+
+            for path in sorted_entries(&dir)? {
+                let gray = match extension(&path).as_deref() {
+                    Some("png") => decode_png(&path)?,
+                    Some("pgm") => decode_pgm(&path)?,
+                    _ => continue,
+                };
+                observations.extend(grayscale_to_observation(&gray));
+                labels.push(label);
+            }
+        }
+
+        let n_observations = labels.len();
+        let observations =
+            Array2::from_shape_vec((n_observations, IMAGE_SIZE * IMAGE_SIZE), observations)
+                .unwrap();
+        Dataset::from_labels(observations, Array1::from_vec(labels), n_classes)
+            .map_err(ImageError::Dataset)
+    }
+}
+
+fn label_dirs(root: &Path) -> Result<Vec<(usize, PathBuf)>, ImageError> {
+    let mut dirs: Vec<(usize, PathBuf)> = sorted_entries(root)?
+        .into_iter()
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let label = path.file_name()?.to_str()?.parse().ok()?;
+            Some((label, path))
+        })
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>, ImageError> {
+    let mut entries = fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|e| e.map(|e| e.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| ImageError::Io(dir.to_path_buf(), e))?;
+    entries.sort();
+    Ok(entries)
+}
+
+fn extension(path: &Path) -> Option<String> {
+    Some(path.extension()?.to_str()?.to_ascii_lowercase())
+}
+
+/// Decode a PNG into grayscale, transparent pixels are composited on white
+fn decode_png(path: &Path) -> Result<Array2<f64>, ImageError> {
+    let file = File::open(path).map_err(|e| ImageError::Io(path.to_path_buf(), e))?;
+    let mut decoder = png::Decoder::new(BufReader::new(file));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+    let png_error = |e| ImageError::Png(path.to_path_buf(), e);
+    let mut reader = decoder.read_info().map_err(png_error)?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(png_error)?;
+
+    let (width, height) = (info.width as usize, info.height as usize);
+    let channels = info.color_type.samples();
+    let pixels = buf[..info.buffer_size()].chunks_exact(channels).map(|p| {
+        let (color, alpha) = match p.len() {
+            1 => (p[0] as f64, 255.0),
+            2 => (p[0] as f64, p[1] as f64),
+            3 => (luma(p), 255.0),
+            _ => (luma(p), p[3] as f64),
+        };
+        over_white(color, alpha)
+    });
+
+    Ok(Array2::from_shape_vec((height, width), pixels.collect()).unwrap())
+}
+
+/// Decode a binary (P5) or plain (P2) PGM, values are scaled to 0..=255
+/// - https://netpbm.sourceforge.net/doc/pgm.html
+fn decode_pgm(path: &Path) -> Result<Array2<f64>, ImageError> {
+    let mut data = Vec::new();
+    File::open(path)
+        .and_then(|mut f| f.read_to_end(&mut data))
+        .map_err(|e| ImageError::Io(path.to_path_buf(), e))?;
+    let invalid = || ImageError::Pgm(path.to_path_buf());
+
+    // header: magic, width, height, maxval separated by whitespace, `#` starts a comment
+    let mut pos = 0;
+    let mut header = Vec::with_capacity(4);
+    while header.len() < 4 {
+        while pos < data.len() && (data[pos].is_ascii_whitespace() || data[pos] == b'#') {
+            if data[pos] == b'#' {
+                while pos < data.len() && data[pos] != b'\n' {
+                    pos += 1;
+                }
+            }
+            pos += 1;
+        }
+        let start = pos;
+        while pos < data.len() && !data[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if start == pos {
+            return Err(invalid());
+        }
+        header.push(String::from_utf8_lossy(&data[start..pos]).to_string());
+    }
+
+    let parse = |s: &str| s.parse::<usize>().map_err(|_| invalid());
+    let (width, height, maxval) = (parse(&header[1])?, parse(&header[2])?, parse(&header[3])?);
+    if maxval == 0 || maxval > u16::MAX as usize {
+        return Err(invalid());
+    }
+    let n_pixels = width.checked_mul(height).ok_or_else(invalid)?;
+
+    let values: Vec<usize> = match header[0].as_str() {
+        "P5" => {
+            // exactly one whitespace character between the header and the raster
+            let raster = data.get(pos + 1..).ok_or_else(invalid)?;
+            if maxval < 256 {
+                raster.iter().map(|&v| v as usize).collect()
+            } else {
+                raster
+                    .chunks_exact(2)
+                    .map(|v| u16::from_be_bytes([v[0], v[1]]) as usize)
+                    .collect()
+            }
+        }
+        "P2" => String::from_utf8_lossy(&data[pos..])
+            .split_ascii_whitespace()
+            .map(parse)
+            .collect::<Result<_, _>>()?,
+        _ => return Err(invalid()),
+    };
+    if values.len() < n_pixels {
+        return Err(invalid());
+    }
+
+    let scale = 255.0 / maxval as f64;
+    let pixels = values[..n_pixels].iter().map(|&v| v as f64 * scale);
+    Ok(Array2::from_shape_vec((height, width), pixels.collect()).unwrap())
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(PathBuf, std::io::Error),
+    Png(PathBuf, png::DecodingError),
+    Pgm(PathBuf),
+    Label {
+        label: usize,
+        n_classes: usize,
+    },
+    /// Decoded images and labels don't make a dataset
+    Dataset(DatasetError),
+}
+impl Error for ImageError {}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            ImageError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ImageError::Png(path, e) => write!(f, "{}: {}", path.display(), e),
+            ImageError::Pgm(path) => write!(f, "{}: invalid PGM image", path.display()),
+            ImageError::Label { label, n_classes } => {
+                write!(f, "Label {} is out of range 0..{}", label, n_classes)
+            }
+            ImageError::Dataset(e) => e.fmt(f),
+        }
+    }
+}
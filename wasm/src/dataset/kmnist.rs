@@ -0,0 +1,35 @@
+use super::{
+    mnist::{dataset_from_gz_idx, IdxFormat},
+    Dataset, IdxError,
+};
+use std::io::Read;
+use wasm_bindgen::prelude::*;
+
+const KMNIST_FORMAT: IdxFormat = IdxFormat {
+    class_names: &["お", "き", "す", "つ", "な", "は", "ま", "や", "れ", "を"],
+    label_offset: 0,
+    transposed: false,
+};
+
+/// Kuzushiji-MNIST, cursive Japanese (hiragana) characters in the MNIST format
+/// - https://github.com/rois-codh/kmnist
+#[wasm_bindgen]
+pub struct KMNIST;
+
+#[wasm_bindgen]
+impl KMNIST {
+    /// Decode KMNIST dataset, n_observations is taken from the file
+    pub fn from_gz(
+        images_idx3_ubyte_gz: &[u8],
+        labels_idx1_ubyte_gz: &[u8],
+    ) -> Result<Dataset, JsError> {
+        Ok(Self::read_gz(images_idx3_ubyte_gz, labels_idx1_ubyte_gz)?)
+    }
+}
+
+impl KMNIST {
+    /// Decode KMNIST dataset from gzip compressed readers
+    pub fn read_gz<R: Read>(images: R, labels: R) -> Result<Dataset, IdxError> {
+        dataset_from_gz_idx(images, labels, None, &KMNIST_FORMAT)
+    }
+}
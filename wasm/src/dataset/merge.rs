@@ -0,0 +1,295 @@
+//! Build and grow datasets, e.g. a personal dataset of corrected canvas drawings
+use super::{Dataset, Observations};
+use ndarray::{Array1, Array2, Axis};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Dataset {
+    /// Observations one after another, `data_size` values each, with their
+    /// labels in 0..n_classes
+    #[wasm_bindgen(constructor)]
+    pub fn js_new(
+        observations: &[f64],
+        data_size: usize,
+        labels: &[u32],
+        n_classes: usize,
+    ) -> Result<Dataset, JsError> {
+        let labels = labels.iter().map(|&l| l as usize).collect();
+        Ok(Self::from_raw(
+            observations.to_vec(),
+            data_size,
+            labels,
+            n_classes,
+        )?)
+    }
+
+    /// Same as the constructor with pixels stored as u8
+    pub fn from_u8(
+        observations: &[u8],
+        data_size: usize,
+        labels: &[u32],
+        n_classes: usize,
+    ) -> Result<Dataset, JsError> {
+        let labels = labels.iter().map(|&l| l as usize).collect();
+        Ok(Self::from_raw(
+            observations.to_vec(),
+            data_size,
+            labels,
+            n_classes,
+        )?)
+    }
+
+    #[wasm_bindgen(js_name = concat)]
+    pub fn js_concat(&self, other: &Dataset) -> Result<Dataset, JsError> {
+        Ok(self.concat(other)?)
+    }
+
+    #[wasm_bindgen(js_name = push)]
+    pub fn js_push(&mut self, observation: &[f64], label: usize) -> Result<(), JsError> {
+        Ok(self.push(observation, label)?)
+    }
+}
+
+impl Dataset {
+    /// Dataset with one-hot targets built from labels in 0..n_classes
+    pub fn from_labels(
+        observations: impl Into<Observations>,
+        labels: Array1<usize>,
+        n_classes: usize,
+    ) -> Result<Dataset, DatasetError> {
+        if let Some(&label) = labels.iter().find(|&&l| l >= n_classes) {
+            return Err(DatasetError::Label { label, n_classes });
+        }
+
+        let mut targets: Array2<f64> = Array2::zeros((labels.len(), n_classes));
+        targets
+            .axis_iter_mut(Axis(0))
+            .zip(&labels)
+            .for_each(|(mut t, &l)| t[l] = 1.0);
+
+        Dataset::new(observations, targets, labels)
+    }
+
+    /// Observations one after another, `data_size` values each
+    pub fn from_raw<A>(
+        observations: Vec<A>,
+        data_size: usize,
+        labels: Vec<usize>,
+        n_classes: usize,
+    ) -> Result<Dataset, DatasetError>
+    where
+        Array2<A>: Into<Observations>,
+    {
+        let expected = labels.len() * data_size;
+        if observations.len() != expected {
+            return Err(DatasetError::Length {
+                expected,
+                actual: observations.len(),
+            });
+        }
+
+        let observations = Array2::from_shape_vec((labels.len(), data_size), observations).unwrap();
+        Self::from_labels(observations, Array1::from_vec(labels), n_classes)
+    }
+
+    /// Observations of `self` followed by those of `other`
+    ///
+    /// Observations are stored in the more precise of the two element types,
+    /// class names are taken from whichever dataset has them and must match
+    /// when both do.
+    pub fn concat(&self, other: &Dataset) -> Result<Dataset, DatasetError> {
+        // the classes of an empty dataset are still known
+        if other.target_size() != self.target_size() {
+            return Err(DatasetError::TargetSize {
+                expected: self.target_size(),
+                actual: other.target_size(),
+            });
+        }
+        if other.n_observations() == 0 {
+            return Ok(self.clone());
+        }
+        if self.n_observations() == 0 && self.class_names.is_empty() {
+            return Ok(other.clone());
+        }
+        if other.data_size() != self.data_size() {
+            return Err(DatasetError::DataSize {
+                expected: self.data_size(),
+                actual: other.data_size(),
+            });
+        }
+
+        if !self.class_names.is_empty()
+            && !other.class_names.is_empty()
+            && self.class_names != other.class_names
+        {
+            return Err(DatasetError::ClassNames {
+                expected: self.class_names.clone(),
+                actual: other.class_names.clone(),
+            });
+        }
+
+        let class_names = if self.class_names.is_empty() {
+            &other.class_names
+        } else {
+            &self.class_names
+        };
+
+        Ok(Dataset {
+            // shapes are checked above
+            observations: self.observations.concat(&other.observations).unwrap(),
+            targets: ndarray::concatenate(Axis(0), &[self.targets.view(), other.targets.view()])
+                .unwrap(),
+            labels: ndarray::concatenate(Axis(0), &[self.labels.view(), other.labels.view()])
+                .unwrap(),
+            class_names: class_names.clone(),
+        })
+    }
+
+    /// Append an observation with its label, rounded and clamped to 0..=255
+    /// when stored as u8
+    ///
+    /// The first observation pushed to an empty dataset sets its data_size.
+    pub fn push(&mut self, observation: &[f64], label: usize) -> Result<(), DatasetError> {
+        if label >= self.target_size() {
+            return Err(DatasetError::Label {
+                label,
+                n_classes: self.target_size(),
+            });
+        }
+        if observation.len() != self.data_size() {
+            if self.n_observations() > 0 {
+                return Err(DatasetError::DataSize {
+                    expected: self.data_size(),
+                    actual: observation.len(),
+                });
+            }
+            self.observations = Observations::F64(Array2::zeros((0, observation.len())))
+                .into_type(self.observation_type());
+        }
+
+        let mut target = Array1::zeros(self.target_size());
+        target[label] = 1.0;
+
+        // shapes are checked above
+        self.observations
+            .push(Array1::from(observation.to_vec()).view())
+            .unwrap();
+        self.targets.push_row(target.view()).unwrap();
+        self.labels
+            .append(Axis(0), Array1::from_elem(1, label).view())
+            .unwrap();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum DatasetError {
+    /// Number of targets or labels differs from the number of observations
+    CountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    /// Raw observations don't hold data_size values for every label
+    Length {
+        expected: usize,
+        actual: usize,
+    },
+    /// Dataset size differs from the one something was built for
+    ObservationCount {
+        expected: usize,
+        actual: usize,
+    },
+    DataSize {
+        expected: usize,
+        actual: usize,
+    },
+    TargetSize {
+        expected: usize,
+        actual: usize,
+    },
+    Label {
+        label: usize,
+        n_classes: usize,
+    },
+    /// Both datasets name their classes, differently
+    ClassNames {
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+    /// Dataset is too large for a quadratic operation
+    TooManyObservations {
+        max: usize,
+        actual: usize,
+    },
+    /// Dataset is empty, or smaller than a batch whose remainder is dropped
+    NoBatch {
+        n_observations: usize,
+        batch_size: usize,
+    },
+}
+impl Error for DatasetError {}
+
+impl Display for DatasetError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            DatasetError::CountMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Expected {} targets and labels but got {}",
+                    expected, actual
+                )
+            }
+            DatasetError::Length { expected, actual } => {
+                write!(f, "Expected {} values but got {}", expected, actual)
+            }
+            DatasetError::ObservationCount { expected, actual } => {
+                write!(
+                    f,
+                    "Expected a dataset of {} observations but got {}",
+                    expected, actual
+                )
+            }
+            DatasetError::DataSize { expected, actual } => {
+                write!(
+                    f,
+                    "Expected observations of size {} but got {}",
+                    expected, actual
+                )
+            }
+            DatasetError::TargetSize { expected, actual } => {
+                write!(f, "Expected {} classes but got {}", expected, actual)
+            }
+            DatasetError::Label { label, n_classes } => {
+                write!(f, "Label {} is out of range 0..{}", label, n_classes)
+            }
+            DatasetError::ClassNames { expected, actual } => {
+                write!(
+                    f,
+                    "Expected class names {:?} but got {:?}",
+                    expected, actual
+                )
+            }
+            DatasetError::TooManyObservations { max, actual } => {
+                write!(
+                    f,
+                    "Expected at most {} observations but got {}",
+                    max, actual
+                )
+            }
+            DatasetError::NoBatch {
+                n_observations,
+                batch_size,
+            } => {
+                write!(
+                    f,
+                    "No batch of {} in a dataset of {} observations",
+                    batch_size, n_observations
+                )
+            }
+        }
+    }
+}
@@ -1,127 +1,249 @@
-use super::Dataset;
-use flate2::read::GzDecoder;
-use ndarray::{Array, Array1, Array2, Axis, Dimension, StrideShape};
-use std::{
-    error::Error,
-    fmt::{Display, Formatter},
-    io::Read,
+use super::{
+    idx::{
+        read_idx, read_idx_data, read_idx_header, write_gz_idx, write_idx, IdxError, IdxHeader,
+        IdxType,
+    },
+    Dataset, Observations,
 };
+use crate::preprocess::IMAGE_SIZE;
+use flate2::read::GzDecoder;
+use ndarray::{Array1, Array2, ArrayD, CowArray, IxDyn};
+use std::io::{Read, Write};
 use wasm_bindgen::prelude::*;
 
-const DATA_SIZE: usize = 28 * 28;
-const DATA_TYPES: usize = 10;
 const TRAIN_NUM: usize = 60_000;
 const TEST_NUM: usize = 10_000;
 
 // const DATA_MAGIC_NUMBER: u32 = 2051;
 // const LABEL_MAGIC_NUMBER: u32 = 2049;
 
+const MNIST_FORMAT: IdxFormat = IdxFormat {
+    class_names: &["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"],
+    label_offset: 0,
+    transposed: false,
+};
+
+/// Layout of a 28x28 IDX image corpus
+pub(super) struct IdxFormat {
+    pub class_names: &'static [&'static str],
+    /// Label of the first class (EMNIST letters start at 1)
+    pub label_offset: usize,
+    /// Images are stored column-major (EMNIST)
+    pub transposed: bool,
+}
+
 #[wasm_bindgen]
 pub struct MNIST;
 
+#[cfg(feature = "bundled-mnist")]
 #[wasm_bindgen]
 impl MNIST {
     /// Get MNIST training dataset (n_observation=60,000) from static binary bundled with the WASM
     pub fn training_from_static() -> Dataset {
         let training_images = include_bytes!("./data/train-images-idx3-ubyte.gz").as_slice();
         let training_labels = include_bytes!("./data/train-labels-idx1-ubyte.gz").as_slice();
-        dataset_from_gz_idx_ubyte(training_images, training_labels, TRAIN_NUM).unwrap()
+        dataset_from_gz_idx(
+            training_images,
+            training_labels,
+            Some(TRAIN_NUM),
+            &MNIST_FORMAT,
+        )
+        .unwrap()
     }
 
     /// Get MNIST testing dataset (n_observation=10,000) from static binary bundled with the WASM
     pub fn testing_from_static() -> Dataset {
         let testing_images = include_bytes!("./data/t10k-images-idx3-ubyte.gz").as_slice();
         let testing_labels = include_bytes!("./data/t10k-labels-idx1-ubyte.gz").as_slice();
-        dataset_from_gz_idx_ubyte(testing_images, testing_labels, TEST_NUM).unwrap()
+        dataset_from_gz_idx(
+            testing_images,
+            testing_labels,
+            Some(TEST_NUM),
+            &MNIST_FORMAT,
+        )
+        .unwrap()
     }
+}
 
+#[wasm_bindgen]
+impl MNIST {
     /// Decode MNIST training dataset (n_observation=60,000)
-    pub fn training_from_gz(images_idx3_ubyte_gz: &[u8], labels_idx1_ubyte_gz: &[u8]) -> Dataset {
-        dataset_from_gz_idx_ubyte(images_idx3_ubyte_gz, labels_idx1_ubyte_gz, TRAIN_NUM).unwrap()
+    pub fn training_from_gz(
+        images_idx3_ubyte_gz: &[u8],
+        labels_idx1_ubyte_gz: &[u8],
+    ) -> Result<Dataset, JsError> {
+        Ok(Self::read_training_gz(
+            images_idx3_ubyte_gz,
+            labels_idx1_ubyte_gz,
+        )?)
     }
 
     /// Decode MNIST testing dataset (n_observation=10,000)
-    pub fn testing_from_gz(images_idx3_ubyte_gz: &[u8], labels_idx1_ubyte_gz: &[u8]) -> Dataset {
-        dataset_from_gz_idx_ubyte(images_idx3_ubyte_gz, labels_idx1_ubyte_gz, TEST_NUM).unwrap()
+    pub fn testing_from_gz(
+        images_idx3_ubyte_gz: &[u8],
+        labels_idx1_ubyte_gz: &[u8],
+    ) -> Result<Dataset, JsError> {
+        Ok(Self::read_testing_gz(
+            images_idx3_ubyte_gz,
+            labels_idx1_ubyte_gz,
+        )?)
+    }
+
+    /// Decode a MNIST formatted dataset, n_observations is taken from the file
+    pub fn from_gz(
+        images_idx3_ubyte_gz: &[u8],
+        labels_idx1_ubyte_gz: &[u8],
+    ) -> Result<Dataset, JsError> {
+        Ok(Self::read_gz(images_idx3_ubyte_gz, labels_idx1_ubyte_gz)?)
+    }
+
+    /// Decode an uncompressed MNIST formatted dataset, n_observations is taken from the file
+    pub fn from_idx(
+        images_idx3_ubyte: &[u8],
+        labels_idx1_ubyte: &[u8],
+    ) -> Result<Dataset, JsError> {
+        Ok(Self::read_idx(images_idx3_ubyte, labels_idx1_ubyte)?)
     }
 }
 
-fn dataset_from_gz_idx_ubyte<R: Read>(
-    images: R,
-    labels: R,
-    num: usize,
-) -> Result<Dataset, IdxError> {
-    let observations: Array2<f64> = array_from_gz_idx_ubyte(images, (num, DATA_SIZE))?;
-    let labels: Array1<usize> = array_from_gz_idx_ubyte(labels, num)?;
-    let mut targets: Array2<f64> = Array2::zeros((num, DATA_TYPES));
+impl MNIST {
+    /// Decode MNIST training dataset (n_observation=60,000) from gzip compressed readers
+    pub fn read_training_gz<R: Read>(images: R, labels: R) -> Result<Dataset, IdxError> {
+        dataset_from_gz_idx(images, labels, Some(TRAIN_NUM), &MNIST_FORMAT)
+    }
 
-    targets
-        .axis_iter_mut(Axis(0))
-        .zip(&labels)
-        .for_each(|(mut t, &l)| t[l] = 1.0);
+    /// Decode MNIST testing dataset (n_observation=10,000) from gzip compressed readers
+    pub fn read_testing_gz<R: Read>(images: R, labels: R) -> Result<Dataset, IdxError> {
+        dataset_from_gz_idx(images, labels, Some(TEST_NUM), &MNIST_FORMAT)
+    }
 
-    Ok(Dataset::new(observations, targets, labels))
-}
+    /// Decode a MNIST formatted dataset of any size from gzip compressed readers
+    pub fn read_gz<R: Read>(images: R, labels: R) -> Result<Dataset, IdxError> {
+        dataset_from_gz_idx(images, labels, None, &MNIST_FORMAT)
+    }
 
-fn array_from_gz_idx_ubyte<R, Sh, D, A>(r: R, shape: Sh) -> Result<Array<A, D>, IdxError>
-where
-    R: Read,
-    D: Dimension,
-    Sh: Into<StrideShape<D>>,
-    A: From<u8> + 'static,
-{
-    let file = GzDecoder::new(r);
-    array_from_idx_ubyte(file, shape)
-}
+    /// Decode a MNIST formatted dataset of any size from uncompressed readers
+    pub fn read_idx<R: Read>(images: R, labels: R) -> Result<Dataset, IdxError> {
+        dataset_from_idx(images, labels, None, &MNIST_FORMAT)
+    }
 
-fn array_from_idx_ubyte<R, Sh, D, A>(mut r: R, shape: Sh) -> Result<Array<A, D>, IdxError>
-where
-    R: Read,
-    D: Dimension,
-    Sh: Into<StrideShape<D>>,
-    A: From<u8> + 'static,
-{
-    let expected = [0, 0, 0x08, 0];
-    let mut actual = [0, 0, 0, 0];
-    r.read_exact(&mut actual).map_err(IdxError::Io)?;
-    if actual[0] != expected[0] || actual[1] != expected[1] || actual[2] != expected[2] {
-        return Err(IdxError::Magic { expected, actual });
+    /// Encode a dataset as `*-idx3-ubyte` images and `*-idx1-ubyte` labels
+    ///
+    /// Observations of size 28 * 28 are written as (n_observations, 28, 28),
+    /// anything else as (n_observations, data_size). Observations keep their
+    /// stored element type, labels are written as u8 unless there are more
+    /// than 256 classes.
+    ///
+    /// Labels are written as stored, starting at 0. Files labelled from 1,
+    /// e.g. EMNIST letters, are read back correctly only after writing
+    /// `dataset.labels() + 1` with `write_idx` instead.
+    pub fn write_idx<W: Write>(dataset: &Dataset, images: W, labels: W) -> Result<(), IdxError> {
+        match dataset.stored_observations() {
+            Observations::U8(o) => write_idx(images, IdxType::U8, &as_images(o)?)?,
+            Observations::F32(o) => write_idx(images, IdxType::F32, &as_images(o)?)?,
+            Observations::F64(o) => write_idx(images, IdxType::F64, &as_images(o)?)?,
+        }
+        write_idx(labels, labels_type(dataset), dataset.labels())
     }
 
-    let mut size = [0; 4];
-    for _ in 0..actual[3] {
-        r.read_exact(&mut size).map_err(IdxError::Io)?;
+    /// Encode a dataset as gzip compressed `*-idx3-ubyte.gz` and `*-idx1-ubyte.gz`,
+    /// see `write_idx`
+    pub fn write_gz<W: Write>(dataset: &Dataset, images: W, labels: W) -> Result<(), IdxError> {
+        match dataset.stored_observations() {
+            Observations::U8(o) => write_gz_idx(images, IdxType::U8, &as_images(o)?)?,
+            Observations::F32(o) => write_gz_idx(images, IdxType::F32, &as_images(o)?)?,
+            Observations::F64(o) => write_gz_idx(images, IdxType::F64, &as_images(o)?)?,
+        }
+        write_gz_idx(labels, labels_type(dataset), dataset.labels())
     }
+}
 
-    let mut buf = Vec::new();
-    r.read_to_end(&mut buf).map_err(IdxError::Io)?;
+fn labels_type(dataset: &Dataset) -> IdxType {
+    if dataset.target_size() <= u8::MAX as usize + 1 {
+        IdxType::U8
+    } else {
+        IdxType::I32
+    }
+}
 
-    let arr = Array::from_shape_vec(shape, buf).map_err(IdxError::Shape)?;
+/// View observations as (n_observations, 28, 28) if they are 28 * 28 images
+fn as_images<A: Clone>(observations: &Array2<A>) -> Result<CowArray<'_, A, IxDyn>, IdxError> {
+    let (n, data_size) = observations.dim();
+    let shape = if data_size == IMAGE_SIZE * IMAGE_SIZE {
+        vec![n, IMAGE_SIZE, IMAGE_SIZE]
+    } else {
+        vec![n, data_size]
+    };
+    observations.to_shape(shape).map_err(IdxError::Shape)
+}
 
-    Ok(arr.mapv_into_any(|v| v.into()))
+pub(super) fn dataset_from_gz_idx<R: Read>(
+    images: R,
+    labels: R,
+    num: Option<usize>,
+    format: &IdxFormat,
+) -> Result<Dataset, IdxError> {
+    dataset_from_idx(GzDecoder::new(images), GzDecoder::new(labels), num, format)
 }
 
-#[derive(Debug)]
-enum IdxError {
-    Io(std::io::Error),
-    Shape(ndarray::ShapeError),
-    Magic { expected: [u8; 4], actual: [u8; 4] },
+/// Decode images and labels, `num` defaults to the number of images in the header
+pub(super) fn dataset_from_idx<R: Read>(
+    mut images: R,
+    labels: R,
+    num: Option<usize>,
+    format: &IdxFormat,
+) -> Result<Dataset, IdxError> {
+    let header = read_idx_header(&mut images)?;
+    let num = num.or_else(|| header.dims.first().copied()).unwrap_or(0);
+    check_dims(&header, &[num, IMAGE_SIZE, IMAGE_SIZE])?;
+    // keep u8 pixels and f32 compact, the integer types are stored as f64
+    let observations: Observations = match header.data_type {
+        IdxType::U8 => to_observations(read_idx_data::<u8, _>(images, &header)?, format)?.into(),
+        IdxType::F32 => to_observations(read_idx_data::<f32, _>(images, &header)?, format)?.into(),
+        _ => to_observations(read_idx_data::<f64, _>(images, &header)?, format)?.into(),
+    };
+
+    // decoded as f64 so negative and fractional labels aren't cast to a class
+    let (header, labels) = read_idx::<f64, _>(labels)?;
+    check_dims(&header, &[num])?;
+
+    let n_classes = format.class_names.len();
+    let labels: Array1<f64> = labels.into_dimensionality().map_err(IdxError::Shape)?;
+    let offset = format.label_offset as f64;
+    if let Some(&label) = labels
+        .iter()
+        .find(|&&l| l.fract() != 0.0 || l < offset || l - offset >= n_classes as f64)
+    {
+        return Err(IdxError::Label { label, n_classes });
+    }
+    let labels = labels.mapv(|l| (l - offset) as usize);
+
+    Dataset::from_labels(observations, labels, n_classes)
+        .and_then(|dataset| dataset.with_class_names(format.class_names))
+        .map_err(IdxError::Dataset)
 }
-impl Error for IdxError {}
-
-impl Display for IdxError {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-        match self {
-            IdxError::Io(e) => e.fmt(f),
-            IdxError::Shape(e) => e.fmt(f),
-            IdxError::Magic { expected, actual } => {
-                write!(
-                    f,
-                    "Expected magic number {:?} but got {:?}",
-                    &expected[..3],
-                    &actual[..3]
-                )
-            }
-        }
+
+fn check_dims(header: &IdxHeader, expected: &[usize]) -> Result<(), IdxError> {
+    if header.dims != expected {
+        return Err(IdxError::CountMismatch {
+            expected: expected.to_vec(),
+            actual: header.dims.clone(),
+        });
     }
+    Ok(())
+}
+
+/// Reshape (n_observations, d1, d2, ...) into (n_observations, d1 * d2 * ...)
+fn to_observations<A: Clone>(
+    mut arr: ArrayD<A>,
+    format: &IdxFormat,
+) -> Result<Array2<A>, IdxError> {
+    if format.transposed {
+        arr.swap_axes(1, 2);
+        arr = arr.as_standard_layout().into_owned();
+    }
+
+    let n = arr.shape().first().copied().unwrap_or(1);
+    let data_size = arr.shape().iter().skip(1).product::<usize>();
+    arr.into_shape((n, data_size)).map_err(IdxError::Shape)
 }
@@ -1,36 +1,151 @@
+mod batch;
+mod csv;
+mod emnist;
+mod fashion_mnist;
+mod idx;
+#[cfg(not(target_arch = "wasm32"))]
+mod image_dir;
+mod kmnist;
+mod merge;
 mod mnist;
+mod split;
+mod stats;
+mod storage;
 
+pub use batch::{Batches, DatasetView};
+pub use csv::{CsvError, CsvOptions};
+pub use emnist::EMNIST;
+pub use fashion_mnist::FashionMNIST;
+pub use idx::{
+    read_gz_idx, read_idx, read_idx_data, read_idx_header, write_gz_idx, write_idx, IdxElement,
+    IdxError, IdxHeader, IdxType,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use image_dir::ImageError;
+pub use kmnist::KMNIST;
+pub use merge::DatasetError;
 pub use mnist::MNIST;
+pub use storage::{ObservationType, Observations};
 
-use ndarray::{iter::AxisIter, Array1, Array2, ArrayView1, Axis, Ix1, Zip};
+use ndarray::{Array1, Array2, ArrayView1, CowArray, Ix1, Ix2};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Dataset {
-    observations: Array2<f64>,
+    observations: Observations,
     targets: Array2<f64>,
     labels: Array1<usize>,
+    class_names: Vec<String>,
 }
 
 impl Dataset {
     /// observations and targets should have same number of  (n_observations)
-    pub fn new(observations: Array2<f64>, targets: Array2<f64>, labels: Array1<usize>) -> Self {
-        assert_eq!(observations.dim().0, targets.dim().0);
-        assert_eq!(observations.dim().0, labels.dim());
+    ///
+    /// Returns an error when the number of observations, targets and labels
+    /// differ or a label has no target.
+    pub fn new(
+        observations: impl Into<Observations>,
+        targets: Array2<f64>,
+        labels: Array1<usize>,
+    ) -> Result<Self, DatasetError> {
+        let observations = observations.into();
+        let n = observations.dim().0;
+        for actual in [targets.nrows(), labels.len()] {
+            if actual != n {
+                return Err(DatasetError::CountMismatch {
+                    expected: n,
+                    actual,
+                });
+            }
+        }
+        // labels index one buffer per class, e.g. in stratified splits
+        if let Some(&label) = labels.iter().find(|&&l| l >= targets.ncols()) {
+            return Err(DatasetError::Label {
+                label,
+                n_classes: targets.ncols(),
+            });
+        }
 
-        Self {
+        Ok(Self {
             observations,
             targets,
             labels,
+            class_names: Vec::new(),
+        })
+    }
+
+    /// Copy of the dataset with its observations replaced, e.g. after a transform
+    pub fn with_observations(
+        &self,
+        observations: impl Into<Observations>,
+    ) -> Result<Self, DatasetError> {
+        let observations = observations.into();
+        if observations.dim().0 != self.n_observations() {
+            return Err(DatasetError::ObservationCount {
+                expected: self.n_observations(),
+                actual: observations.dim().0,
+            });
         }
+
+        Ok(self.with_transformed_observations(observations))
+    }
+
+    /// `with_observations` for transforms that keep one row per observation,
+    /// panics on another number of rows
+    pub(crate) fn with_transformed_observations(
+        &self,
+        observations: impl Into<Observations>,
+    ) -> Self {
+        let observations = observations.into();
+        assert_eq!(observations.dim().0, self.n_observations());
+
+        Self {
+            observations,
+            targets: self.targets.clone(),
+            labels: self.labels.clone(),
+            class_names: self.class_names.clone(),
+        }
+    }
+
+    /// Attach a name to each class, indexed by label
+    pub fn with_class_names(mut self, class_names: &[&str]) -> Result<Self, DatasetError> {
+        if class_names.len() != self.target_size() {
+            return Err(DatasetError::TargetSize {
+                expected: self.target_size(),
+                actual: class_names.len(),
+            });
+        }
+
+        self.class_names = class_names.iter().map(|name| name.to_string()).collect();
+        Ok(self)
     }
 
     /// Shape: (n_observations, data_size)
-    pub fn observations(&self) -> &Array2<f64> {
+    ///
+    /// Converted to f64 unless stored as f64, use `observation_chunks` to
+    /// avoid converting the whole dataset at once.
+    pub fn observations(&self) -> CowArray<'_, f64, Ix2> {
+        self.observations.to_f64()
+    }
+
+    /// Observations in their stored element type
+    pub fn stored_observations(&self) -> &Observations {
         &self.observations
     }
 
+    /// Observations in their stored element type, for in place edits that
+    /// keep the number of observations
+    pub(crate) fn stored_observations_mut(&mut self) -> &mut Observations {
+        &mut self.observations
+    }
+
+    pub fn observation(&self, i: usize) -> CowArray<'_, f64, Ix1> {
+        self.observations.row(i)
+    }
+
     /// Shape: (n_observations, target_size)
     pub fn targets(&self) -> &Array2<f64> {
         &self.targets
@@ -40,6 +155,11 @@ impl Dataset {
         &self.labels
     }
 
+    /// Name of each class indexed by label, empty if unknown
+    pub fn class_names(&self) -> &[String] {
+        &self.class_names
+    }
+
     pub fn n_observations(&self) -> usize {
         self.observations.dim().0
     }
@@ -52,18 +172,58 @@ impl Dataset {
         self.targets.dim().1
     }
 
-    pub fn at(&self, i: usize) -> (ArrayView1<f64>, ArrayView1<f64>) {
+    pub fn at(&self, i: usize) -> (CowArray<'_, f64, Ix1>, ArrayView1<'_, f64>) {
         (self.observations.row(i), self.targets.row(i))
     }
 
-    pub fn observation_target_iter(&self) -> ObservationTargetIter {
-        Zip::from(self.observations.axis_iter(Axis(0))).and(self.targets.axis_iter(Axis(0)))
+    pub fn observation_target_iter(
+        &self,
+    ) -> impl Iterator<Item = (CowArray<'_, f64, Ix1>, ArrayView1<'_, f64>)> {
+        (0..self.n_observations()).map(move |i| self.at(i))
     }
 
-    pub fn observation_label_iter(&self) -> ObservationLabelIter {
-        Zip::from(self.observations().axis_iter(Axis(0))).and(self.labels())
+    pub fn observation_iter(&self) -> impl Iterator<Item = CowArray<'_, f64, Ix1>> {
+        (0..self.n_observations()).map(move |i| self.observation(i))
+    }
+
+    pub fn observation_label_iter(&self) -> impl Iterator<Item = (CowArray<'_, f64, Ix1>, usize)> {
+        (0..self.n_observations()).map(move |i| (self.observation(i), self.labels[i]))
+    }
+
+    /// Consecutive observations converted to f64 `chunk_size` at a time, with
+    /// their range of indices
+    pub fn observation_chunks(
+        &self,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = (Range<usize>, CowArray<'_, f64, Ix2>)> {
+        let n = self.n_observations();
+        (0..n).step_by(chunk_size.max(1)).map(move |start| {
+            let range = start..(start + chunk_size).min(n);
+            (range.clone(), self.observations.rows(range))
+        })
     }
 }
 
-type ObservationTargetIter<'a> = Zip<(AxisIter<'a, f64, Ix1>, AxisIter<'a, f64, Ix1>), Ix1>;
-type ObservationLabelIter<'a> = Zip<(AxisIter<'a, f64, Ix1>, ArrayView1<'a, usize>), Ix1>;
+#[wasm_bindgen]
+impl Dataset {
+    /// Name of each class indexed by label, empty if unknown
+    #[wasm_bindgen(getter = class_names)]
+    pub fn js_class_names(&self) -> Vec<JsValue> {
+        self.class_names.iter().map(|name| name.into()).collect()
+    }
+
+    #[wasm_bindgen(getter = n_observations)]
+    pub fn js_n_observations(&self) -> usize {
+        self.n_observations()
+    }
+
+    pub fn observation_type(&self) -> ObservationType {
+        self.observations.observation_type()
+    }
+
+    /// Convert how observations are stored, u8 rounds and clamps to 0..=255
+    pub fn into_observation_type(mut self, observation_type: ObservationType) -> Dataset {
+        self.observations = self.observations.into_type(observation_type);
+        self
+    }
+}
@@ -0,0 +1,80 @@
+use super::Dataset;
+use ndarray::Axis;
+use ndarray_rand::rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+impl Dataset {
+    /// New dataset with the observations at `indices`, in that order
+    pub fn select(&self, indices: &[usize]) -> Dataset {
+        Dataset {
+            observations: self.observations.select(indices),
+            targets: self.targets.select(Axis(0), indices),
+            labels: self.labels.select(Axis(0), indices),
+            class_names: self.class_names.clone(),
+        }
+    }
+
+    /// First `n` observations
+    pub fn take(&self, n: usize) -> Dataset {
+        let indices: Vec<usize> = (0..n.min(self.n_observations())).collect();
+        self.select(&indices)
+    }
+
+    /// Observations with one of the given labels, labels are kept as is
+    pub fn filter_labels(&self, labels: &[usize]) -> Dataset {
+        let indices: Vec<usize> = self
+            .labels
+            .indexed_iter()
+            .filter(|(_, label)| labels.contains(label))
+            .map(|(i, _)| i)
+            .collect();
+        self.select(&indices)
+    }
+
+    pub fn shuffle(&self, seed: u64) -> Dataset {
+        let mut indices: Vec<usize> = (0..self.n_observations()).collect();
+        indices.shuffle(&mut StdRng::seed_from_u64(seed));
+        self.select(&indices)
+    }
+
+    /// Shuffle then split into (ratio, 1 - ratio) of the observations
+    pub fn split(&self, ratio: f64, seed: u64) -> (Dataset, Dataset) {
+        let mut indices: Vec<usize> = (0..self.n_observations()).collect();
+        indices.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        let (first, second) = indices.split_at(split_point(indices.len(), ratio));
+        (self.select(first), self.select(second))
+    }
+
+    /// Like `split` but each label is split with the same ratio, so both
+    /// datasets keep the class balance
+    pub fn stratified_split(&self, ratio: f64, seed: u64) -> (Dataset, Dataset) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut first = Vec::new();
+        let mut second = Vec::new();
+
+        for mut indices in self.indices_by_label() {
+            indices.shuffle(&mut rng);
+            let (a, b) = indices.split_at(split_point(indices.len(), ratio));
+            first.extend_from_slice(a);
+            second.extend_from_slice(b);
+        }
+
+        // mix the labels back together
+        first.shuffle(&mut rng);
+        second.shuffle(&mut rng);
+        (self.select(&first), self.select(&second))
+    }
+
+    /// Indices of the observations of each label, indexed by label
+    pub(crate) fn indices_by_label(&self) -> Vec<Vec<usize>> {
+        let mut indices = vec![Vec::new(); self.target_size()];
+        for (i, &label) in self.labels.indexed_iter() {
+            indices[label].push(i);
+        }
+        indices
+    }
+}
+
+fn split_point(len: usize, ratio: f64) -> usize {
+    ((len as f64 * ratio.clamp(0.0, 1.0)).round() as usize).min(len)
+}
@@ -0,0 +1,155 @@
+//! Summaries of a dataset to inspect class balance and catch bad data before
+//! training
+use super::{Dataset, DatasetError};
+use crate::models::CHUNK_SIZE;
+use ndarray::{s, Array1, Array2, Axis};
+use wasm_bindgen::prelude::*;
+
+/// Largest dataset `near_duplicates` compares pairwise, about 2 million pairs
+const MAX_NEAR_DUPLICATES_OBSERVATIONS: usize = 2_000;
+
+impl Dataset {
+    /// Number of observations of each label
+    pub fn class_counts(&self) -> Array1<usize> {
+        let mut counts = Array1::zeros(self.target_size());
+        self.labels().iter().for_each(|&l| counts[l] += 1);
+        counts
+    }
+
+    /// Shape: (n_classes, data_size), mean observation of each label, zeros
+    /// for a label without observations
+    pub fn class_means(&self) -> Array2<f64> {
+        let mut sums = Array2::zeros((self.target_size(), self.data_size()));
+        for (range, chunk) in self.observation_chunks(CHUNK_SIZE) {
+            let labels = self.labels().slice(s![range]);
+            for (observation, &label) in chunk.outer_iter().zip(&labels) {
+                let mut sum = sums.row_mut(label);
+                sum += &observation;
+            }
+        }
+
+        let counts = self.class_counts();
+        for (mut sum, &count) in sums.outer_iter_mut().zip(&counts) {
+            if count > 0 {
+                sum /= count as f64;
+            }
+        }
+        sums
+    }
+
+    /// Shape: (data_size), mean of each feature
+    pub fn pixel_mean(&self) -> Array1<f64> {
+        let mut sum = Array1::zeros(self.data_size());
+        for (_, chunk) in self.observation_chunks(CHUNK_SIZE) {
+            sum += &chunk.sum_axis(Axis(0));
+        }
+        sum / self.n_observations().max(1) as f64
+    }
+
+    /// Shape: (data_size), population variance of each feature
+    pub fn pixel_variance(&self) -> Array1<f64> {
+        let mean = self.pixel_mean();
+        let mut sum = Array1::zeros(self.data_size());
+        for (_, chunk) in self.observation_chunks(CHUNK_SIZE) {
+            sum += &(&chunk - &mean).mapv(|v| v * v).sum_axis(Axis(0));
+        }
+        sum / self.n_observations().max(1) as f64
+    }
+
+    /// Number of values in each of `n_bins` equal bins over 0..=255, values
+    /// outside of the range are counted in the first or last bin
+    pub fn intensity_histogram(&self, n_bins: usize) -> Array1<usize> {
+        let mut histogram = Array1::zeros(n_bins);
+        if n_bins == 0 {
+            return histogram;
+        }
+
+        for (_, chunk) in self.observation_chunks(CHUNK_SIZE) {
+            for &v in chunk.iter() {
+                let bin = (v / 256.0 * n_bins as f64).floor().max(0.0) as usize;
+                histogram[bin.min(n_bins - 1)] += 1;
+            }
+        }
+        histogram
+    }
+
+    /// Pairs (i, j) with i < j whose mean absolute difference per feature is
+    /// at most `max_distance`, e.g. 0 for exact duplicates
+    ///
+    /// The difference of the sums of two observations is a lower bound of
+    /// their distance, so only observations with close sums are compared.
+    /// Above 0 most sums of handwritten digits are close and up to every pair
+    /// is compared, O(n_observations²), so datasets of more than 2,000
+    /// observations are an error unless `max_distance` is 0.
+    pub fn near_duplicates(&self, max_distance: f64) -> Result<Vec<(usize, usize)>, DatasetError> {
+        if max_distance > 0.0 && self.n_observations() > MAX_NEAR_DUPLICATES_OBSERVATIONS {
+            return Err(DatasetError::TooManyObservations {
+                max: MAX_NEAR_DUPLICATES_OBSERVATIONS,
+                actual: self.n_observations(),
+            });
+        }
+
+        let max_total = max_distance * self.data_size() as f64;
+        let sums: Vec<f64> = self.observation_iter().map(|o| o.sum()).collect();
+        let mut order: Vec<usize> = (0..self.n_observations()).collect();
+        order.sort_by(|&a, &b| sums[a].total_cmp(&sums[b]));
+
+        let mut pairs = Vec::new();
+        for (k, &i) in order.iter().enumerate() {
+            for &j in order[k + 1..]
+                .iter()
+                .take_while(|&&j| sums[j] - sums[i] <= max_total)
+            {
+                let distance = self.stored_observations().l1_distance(i, j);
+                if distance <= max_total {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        Ok(pairs)
+    }
+}
+
+#[wasm_bindgen]
+impl Dataset {
+    /// Number of observations of each label
+    #[wasm_bindgen(js_name = class_counts)]
+    pub fn js_class_counts(&self) -> Vec<u32> {
+        self.class_counts().iter().map(|&c| c as u32).collect()
+    }
+
+    /// Mean observation of every label, one after another
+    #[wasm_bindgen(js_name = class_means)]
+    pub fn js_class_means(&self) -> Vec<f64> {
+        self.class_means().into_raw_vec()
+    }
+
+    #[wasm_bindgen(js_name = pixel_mean)]
+    pub fn js_pixel_mean(&self) -> Vec<f64> {
+        self.pixel_mean().into_raw_vec()
+    }
+
+    #[wasm_bindgen(js_name = pixel_variance)]
+    pub fn js_pixel_variance(&self) -> Vec<f64> {
+        self.pixel_variance().into_raw_vec()
+    }
+
+    #[wasm_bindgen(js_name = intensity_histogram)]
+    pub fn js_intensity_histogram(&self, n_bins: usize) -> Vec<u32> {
+        self.intensity_histogram(n_bins)
+            .iter()
+            .map(|&c| c as u32)
+            .collect()
+    }
+
+    /// Near duplicate pairs flattened as [i0, j0, i1, j1, ...]
+    #[wasm_bindgen(js_name = near_duplicates)]
+    pub fn js_near_duplicates(&self, max_distance: f64) -> Result<Vec<u32>, JsError> {
+        Ok(self
+            .near_duplicates(max_distance)?
+            .into_iter()
+            .flat_map(|(i, j)| [i as u32, j as u32])
+            .collect())
+    }
+}
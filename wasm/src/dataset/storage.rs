@@ -0,0 +1,214 @@
+use ndarray::{
+    concatenate, s, Array2, ArrayBase, ArrayView1, Axis, CowArray, Data, Ix1, Ix2, ShapeError,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use wasm_bindgen::prelude::*;
+
+/// Element type used to store observations
+///
+/// The 60,000 MNIST training images take 47 MB as u8 and 376 MB as f64, so
+/// pixel data is kept compact and converted to f64 on access.
+///
+/// Ordered from least to most precise
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ObservationType {
+    U8,
+    F32,
+    F64,
+}
+
+/// Shape: (n_observations, data_size)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Observations {
+    U8(Array2<u8>),
+    F32(Array2<f32>),
+    F64(Array2<f64>),
+}
+
+impl Observations {
+    pub fn observation_type(&self) -> ObservationType {
+        match self {
+            Observations::U8(_) => ObservationType::U8,
+            Observations::F32(_) => ObservationType::F32,
+            Observations::F64(_) => ObservationType::F64,
+        }
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        match self {
+            Observations::U8(o) => o.dim(),
+            Observations::F32(o) => o.dim(),
+            Observations::F64(o) => o.dim(),
+        }
+    }
+
+    /// Every observation as f64, only borrowed when stored as f64
+    pub fn to_f64(&self) -> CowArray<'_, f64, Ix2> {
+        self.rows(0..self.dim().0)
+    }
+
+    /// Observation `i` as f64
+    pub fn row(&self, i: usize) -> CowArray<'_, f64, Ix1> {
+        match self {
+            Observations::U8(o) => o.row(i).mapv(f64::from).into(),
+            Observations::F32(o) => o.row(i).mapv(f64::from).into(),
+            Observations::F64(o) => o.row(i).into(),
+        }
+    }
+
+    /// Observations in `range` as f64
+    pub fn rows(&self, range: Range<usize>) -> CowArray<'_, f64, Ix2> {
+        match self {
+            Observations::U8(o) => o.slice(s![range, ..]).mapv(f64::from).into(),
+            Observations::F32(o) => o.slice(s![range, ..]).mapv(f64::from).into(),
+            Observations::F64(o) => o.slice(s![range, ..]).into(),
+        }
+    }
+
+    /// Sum of the absolute differences of observations `i` and `j`, computed
+    /// in the stored element type without converting the rows
+    pub fn l1_distance(&self, i: usize, j: usize) -> f64 {
+        match self {
+            Observations::U8(o) => o
+                .row(i)
+                .iter()
+                .zip(o.row(j))
+                .map(|(&a, &b)| a.abs_diff(b) as f64)
+                .sum(),
+            Observations::F32(o) => o
+                .row(i)
+                .iter()
+                .zip(o.row(j))
+                .map(|(&a, &b)| (f64::from(a) - f64::from(b)).abs())
+                .sum(),
+            Observations::F64(o) => o
+                .row(i)
+                .iter()
+                .zip(o.row(j))
+                .map(|(a, b)| (a - b).abs())
+                .sum(),
+        }
+    }
+
+    /// Squared euclidean distance of every observation to `observation`,
+    /// read in the stored element type without converting the rows
+    pub fn squared_distances(
+        &self,
+        observation: &ArrayBase<impl Data<Elem = f64>, Ix1>,
+    ) -> Vec<f64> {
+        match self {
+            Observations::U8(o) => squared_distances(o, observation),
+            Observations::F32(o) => squared_distances(o, observation),
+            Observations::F64(o) => squared_distances(o, observation),
+        }
+    }
+
+    /// Observations at `indices`, in that order
+    pub fn select(&self, indices: &[usize]) -> Self {
+        match self {
+            Observations::U8(o) => Observations::U8(o.select(Axis(0), indices)),
+            Observations::F32(o) => Observations::F32(o.select(Axis(0), indices)),
+            Observations::F64(o) => Observations::F64(o.select(Axis(0), indices)),
+        }
+    }
+
+    /// Observations of `self` followed by those of `other`, stored in the more
+    /// precise of the two element types
+    pub fn concat(&self, other: &Observations) -> Result<Self, ShapeError> {
+        let observation_type = self.observation_type().max(other.observation_type());
+        let a = self.clone().into_type(observation_type);
+        let b = other.clone().into_type(observation_type);
+
+        match (a, b) {
+            (Observations::U8(a), Observations::U8(b)) => Ok(Observations::U8(concatenate(
+                Axis(0),
+                &[a.view(), b.view()],
+            )?)),
+            (Observations::F32(a), Observations::F32(b)) => Ok(Observations::F32(concatenate(
+                Axis(0),
+                &[a.view(), b.view()],
+            )?)),
+            (Observations::F64(a), Observations::F64(b)) => Ok(Observations::F64(concatenate(
+                Axis(0),
+                &[a.view(), b.view()],
+            )?)),
+            _ => unreachable!("converted to the same element type"),
+        }
+    }
+
+    /// Append an observation, rounded and clamped to 0..=255 for u8
+    pub fn push(&mut self, observation: ArrayView1<f64>) -> Result<(), ShapeError> {
+        match self {
+            Observations::U8(o) => o.push_row(
+                observation
+                    .mapv(|v| v.round().clamp(0.0, 255.0) as u8)
+                    .view(),
+            ),
+            Observations::F32(o) => o.push_row(observation.mapv(|v| v as f32).view()),
+            Observations::F64(o) => o.push_row(observation),
+        }
+    }
+
+    /// Replace observation `i`, rounded and clamped to 0..=255 for u8
+    pub fn set_row(&mut self, i: usize, observation: ArrayView1<f64>) {
+        match self {
+            Observations::U8(o) => o
+                .row_mut(i)
+                .zip_mut_with(&observation, |o, &v| *o = v.round().clamp(0.0, 255.0) as u8),
+            Observations::F32(o) => o
+                .row_mut(i)
+                .zip_mut_with(&observation, |o, &v| *o = v as f32),
+            Observations::F64(o) => o.row_mut(i).assign(&observation),
+        }
+    }
+
+    /// Convert the element type, values are rounded and clamped to 0..=255 for u8
+    pub fn into_type(self, observation_type: ObservationType) -> Self {
+        if self.observation_type() == observation_type {
+            return self;
+        }
+
+        let o = self.to_f64();
+        match observation_type {
+            ObservationType::U8 => Observations::U8(o.mapv(|v| v.round().clamp(0.0, 255.0) as u8)),
+            ObservationType::F32 => Observations::F32(o.mapv(|v| v as f32)),
+            ObservationType::F64 => Observations::F64(o.into_owned()),
+        }
+    }
+}
+
+impl From<Array2<u8>> for Observations {
+    fn from(o: Array2<u8>) -> Self {
+        Observations::U8(o)
+    }
+}
+
+impl From<Array2<f32>> for Observations {
+    fn from(o: Array2<f32>) -> Self {
+        Observations::F32(o)
+    }
+}
+
+impl From<Array2<f64>> for Observations {
+    fn from(o: Array2<f64>) -> Self {
+        Observations::F64(o)
+    }
+}
+
+fn squared_distances<T: Copy + Into<f64>>(
+    observations: &Array2<T>,
+    observation: &ArrayBase<impl Data<Elem = f64>, Ix1>,
+) -> Vec<f64> {
+    observations
+        .rows()
+        .into_iter()
+        .map(|row| {
+            row.iter()
+                .zip(observation)
+                .map(|(&a, &b)| (a.into() - b).powi(2))
+                .sum()
+        })
+        .collect()
+}
@@ -0,0 +1,76 @@
+//! Histograms of oriented gradients
+//!
+//! - https://lear.inrialpes.fr/people/triggs/pubs/Dalal-cvpr05.pdf
+//!
+//! 7x7 pixel cells with 9 unsigned orientation bins, grouped into overlapping
+//! blocks of 2x2 cells normalized with L2-Hys.
+use crate::preprocess::IMAGE_SIZE;
+use ndarray::{s, Array1, Array3, ArrayBase, Data, Ix2};
+use std::f64::consts::PI;
+
+const CELL_SIZE: usize = 7;
+const CELLS: usize = IMAGE_SIZE / CELL_SIZE;
+const BINS: usize = 9;
+/// Cells along each side of a block
+const BLOCK_SIZE: usize = 2;
+const BLOCKS: usize = CELLS - BLOCK_SIZE + 1;
+/// L2-Hys clips the normalized block at this value before normalizing again
+const CLIP: f64 = 0.2;
+
+pub(super) const SIZE: usize = BLOCKS * BLOCKS * BLOCK_SIZE * BLOCK_SIZE * BINS;
+
+pub(super) fn hog(image: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Array1<f64> {
+    let (height, width) = image.dim();
+    // pixels outside the image repeat the edge
+    let pixel = |y: isize, x: isize| {
+        let y = y.clamp(0, height as isize - 1) as usize;
+        let x = x.clamp(0, width as isize - 1) as usize;
+        image[(y, x)]
+    };
+
+    // Shape: (cell_y, cell_x, bin)
+    let mut cells = Array3::<f64>::zeros((CELLS, CELLS, BINS));
+    for y in 0..CELLS * CELL_SIZE {
+        for x in 0..CELLS * CELL_SIZE {
+            let (yi, xi) = (y as isize, x as isize);
+            let gx = pixel(yi, xi + 1) - pixel(yi, xi - 1);
+            let gy = pixel(yi + 1, xi) - pixel(yi - 1, xi);
+            let magnitude = gx.hypot(gy);
+            if magnitude == 0.0 {
+                continue;
+            }
+
+            // unsigned orientation in 0..BINS, split between the two nearest bins
+            let angle = gy.atan2(gx).rem_euclid(PI);
+            let position = angle / PI * BINS as f64 - 0.5;
+            let lower = position.floor();
+            let weight = position - lower;
+            let lower = (lower as isize).rem_euclid(BINS as isize) as usize;
+            let upper = (lower + 1) % BINS;
+
+            let mut cell = cells.slice_mut(s![y / CELL_SIZE, x / CELL_SIZE, ..]);
+            cell[lower] += (1.0 - weight) * magnitude;
+            cell[upper] += weight * magnitude;
+        }
+    }
+
+    let mut descriptor = Vec::with_capacity(SIZE);
+    for by in 0..BLOCKS {
+        for bx in 0..BLOCKS {
+            let block = cells.slice(s![by..by + BLOCK_SIZE, bx..bx + BLOCK_SIZE, ..]);
+            let mut block: Vec<f64> = block.iter().copied().collect();
+            l2_normalize(&mut block);
+            block.iter_mut().for_each(|v| *v = v.min(CLIP));
+            l2_normalize(&mut block);
+            descriptor.extend(block);
+        }
+    }
+    Array1::from_vec(descriptor)
+}
+
+fn l2_normalize(values: &mut [f64]) {
+    let norm = values.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        values.iter_mut().for_each(|v| *v /= norm);
+    }
+}
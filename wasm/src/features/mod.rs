@@ -0,0 +1,87 @@
+//! Hand-crafted features computed from 28x28 observations
+//!
+//! Each extractor turns an image into a shorter descriptor, the resulting
+//! `Dataset` keeps the labels so any model can be trained on it instead of
+//! the raw pixels.
+mod hog;
+mod projection;
+mod zoning;
+
+use hog::hog;
+use projection::projection;
+use zoning::zoning;
+
+use crate::dataset::Dataset;
+use crate::preprocess::IMAGE_SIZE;
+use ndarray::{Array1, Array2, ArrayBase, Data, ErrorKind, Ix2, ShapeError};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// Histograms of oriented gradients, 324 values
+    Hog,
+    /// Mean intensity of 4x4 zones, 16 values
+    Zoning,
+    /// Ink per row followed by ink per column, 56 values
+    Projection,
+}
+
+impl Feature {
+    /// Length of the descriptor
+    pub fn size(self) -> usize {
+        match self {
+            Feature::Hog => hog::SIZE,
+            Feature::Zoning => zoning::SIZE,
+            Feature::Projection => projection::SIZE,
+        }
+    }
+
+    /// Shape: (28, 28) -> (size)
+    ///
+    /// The extractors assume 28x28 images, any other shape is an error.
+    pub fn extract(
+        self,
+        image: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    ) -> Result<Array1<f64>, ShapeError> {
+        if image.dim() != (IMAGE_SIZE, IMAGE_SIZE) {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape));
+        }
+
+        Ok(match self {
+            Feature::Hog => hog(image),
+            Feature::Zoning => zoning(image),
+            Feature::Projection => projection(image),
+        })
+    }
+}
+
+/// Copy of a dataset of 28x28 observations with observations replaced by `feature`
+#[wasm_bindgen(js_name = extract_features)]
+pub fn js_extract_features(dataset: &Dataset, feature: Feature) -> Result<Dataset, JsError> {
+    Ok(extract_features(dataset, feature)?)
+}
+
+/// Same as the JS `extract_features`, for native tools
+pub fn extract_features(dataset: &Dataset, feature: Feature) -> Result<Dataset, ShapeError> {
+    if dataset.data_size() != IMAGE_SIZE * IMAGE_SIZE {
+        return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape));
+    }
+
+    let mut features = Array2::zeros((dataset.n_observations(), feature.size()));
+    for (mut f, observation) in features.outer_iter_mut().zip(dataset.observation_iter()) {
+        let image = observation.to_shape((IMAGE_SIZE, IMAGE_SIZE)).unwrap();
+        f.assign(&feature.extract(&image)?);
+    }
+    Ok(dataset.with_transformed_observations(features))
+}
+
+/// Features of a single 28x28 observation, e.g. the input of `predict`
+#[wasm_bindgen]
+pub fn extract_observation_features(
+    observation: Vec<f64>,
+    feature: Feature,
+) -> Result<Vec<f64>, JsError> {
+    let image = Array2::from_shape_vec((IMAGE_SIZE, IMAGE_SIZE), observation)?;
+    Ok(feature.extract(&image)?.into_raw_vec())
+}
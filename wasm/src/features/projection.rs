@@ -0,0 +1,12 @@
+use crate::preprocess::IMAGE_SIZE;
+use ndarray::{concatenate, Array1, ArrayBase, Axis, Data, Ix2};
+
+pub(super) const SIZE: usize = 2 * IMAGE_SIZE;
+
+/// Horizontal projection (ink in each row) followed by vertical projection
+/// (ink in each column), scaled so a fully inked row or column is 1
+pub(super) fn projection(image: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Array1<f64> {
+    let rows = image.sum_axis(Axis(1)) / (255.0 * image.ncols() as f64);
+    let columns = image.sum_axis(Axis(0)) / (255.0 * image.nrows() as f64);
+    concatenate![Axis(0), rows, columns]
+}
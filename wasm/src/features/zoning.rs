@@ -0,0 +1,20 @@
+use crate::preprocess::IMAGE_SIZE;
+use ndarray::{s, Array1, ArrayBase, Data, Ix2};
+
+/// Number of zones along each side
+const ZONES: usize = 4;
+const ZONE_SIZE: usize = IMAGE_SIZE / ZONES;
+
+pub(super) const SIZE: usize = ZONES * ZONES;
+
+/// Mean intensity of each 7x7 zone scaled to 0..=1, row by row
+pub(super) fn zoning(image: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Array1<f64> {
+    Array1::from_shape_fn(SIZE, |i| {
+        let (y, x) = (i / ZONES * ZONE_SIZE, i % ZONES * ZONE_SIZE);
+        image
+            .slice(s![y..y + ZONE_SIZE, x..x + ZONE_SIZE])
+            .mean()
+            .unwrap()
+            / 255.0
+    })
+}
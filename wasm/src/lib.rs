@@ -1,5 +1,11 @@
+#[macro_use]
+mod macros;
+
 pub mod dataset;
+pub mod features;
 pub mod models;
+pub mod preprocess;
+pub mod validation;
 
 use wasm_bindgen::prelude::*;
 
@@ -0,0 +1,88 @@
+/// `to_json`/`from_json` for JS and `read_json`/`write_json` for native tools
+macro_rules! impl_json {
+    ($t:ident) => {
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        impl $t {
+            pub fn to_json(&self) -> Result<String, wasm_bindgen::JsError> {
+                Ok(serde_json::to_string(self)?)
+            }
+
+            pub fn from_json(json: &str) -> Result<$t, wasm_bindgen::JsError> {
+                Ok(Self::read_json(json.as_bytes())?)
+            }
+        }
+
+        impl $t {
+            pub fn read_json<R: std::io::Read>(r: R) -> Result<$t, serde_json::Error> {
+                serde_json::from_reader(r)
+            }
+
+            pub fn write_json<W: std::io::Write>(&self, w: W) -> Result<(), serde_json::Error> {
+                serde_json::to_writer(w, self)
+            }
+        }
+    };
+}
+
+/// `predict_proba_raw` through the model's optional `normalizer` field, with
+/// `set_normalizer` and JSON saving so the model is saved with its scaling
+macro_rules! impl_normalized_model {
+    ($t:ident) => {
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        impl $t {
+            #[wasm_bindgen(js_name = predict_proba)]
+            pub fn js_predict_proba(
+                &self,
+                observation: Vec<f64>,
+            ) -> Result<Vec<f64>, wasm_bindgen::JsError> {
+                Ok(self.predict_proba_raw(observation)?)
+            }
+
+            #[wasm_bindgen(js_name = predict)]
+            pub fn js_predict(
+                &self,
+                observation: Vec<f64>,
+            ) -> Result<usize, wasm_bindgen::JsError> {
+                Ok(self.predict_raw(observation)?)
+            }
+
+            /// Normalize `predict*` inputs the same way the training dataset was
+            pub fn set_normalizer(&mut self, normalizer: &$crate::preprocess::Normalizer) {
+                self.normalizer = Some(normalizer.clone());
+            }
+        }
+
+        impl $t {
+            /// Class probabilities of an observation before normalization, unlike
+            /// `Model::predict_proba` which takes it normalized
+            pub fn predict_proba_raw(
+                &self,
+                observation: Vec<f64>,
+            ) -> Result<Vec<f64>, $crate::models::ModelError> {
+                let observation = self.normalize_raw(observation)?;
+                Ok($crate::models::Model::predict_proba(self, observation.view()).into_raw_vec())
+            }
+
+            /// Most likely class of an observation before normalization
+            pub fn predict_raw(
+                &self,
+                observation: Vec<f64>,
+            ) -> Result<usize, $crate::models::ModelError> {
+                let observation = self.normalize_raw(observation)?;
+                Ok($crate::models::Model::predict(self, observation.view()))
+            }
+
+            fn normalize_raw(
+                &self,
+                observation: Vec<f64>,
+            ) -> Result<ndarray::Array1<f64>, $crate::models::ModelError> {
+                let observation = $crate::models::normalize(&self.normalizer, observation)
+                    .map_err($crate::models::ModelError::Normalize)?;
+                $crate::models::check_data_size(self, observation.len())?;
+                Ok(observation)
+            }
+        }
+
+        impl_json!($t);
+    };
+}
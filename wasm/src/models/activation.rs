@@ -0,0 +1,64 @@
+//! Activation and loss functions shared by the neural network models
+use ndarray::{Array, Array2, ArrayBase, Axis, Data, Dimension, Ix2};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// Activation of the hidden layers
+#[wasm_bindgen]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Activation {
+    #[default]
+    Relu,
+    Sigmoid,
+    Tanh,
+}
+
+impl Activation {
+    /// a = f(z), element-wise
+    pub(crate) fn apply<D: Dimension>(self, z: &mut Array<f64, D>) {
+        match self {
+            Activation::Relu => z.mapv_inplace(|v| v.max(0.0)),
+            Activation::Sigmoid => z.mapv_inplace(|v| 1.0 / (1.0 + (-v).exp())),
+            Activation::Tanh => z.mapv_inplace(f64::tanh),
+        }
+    }
+
+    /// f'(z) computed from the output a = f(z)
+    pub(crate) fn derivative<D: Dimension>(self, a: &Array<f64, D>) -> Array<f64, D> {
+        match self {
+            Activation::Relu => a.mapv(|v| if v > 0.0 { 1.0 } else { 0.0 }),
+            Activation::Sigmoid => a.mapv(|v| v * (1.0 - v)),
+            Activation::Tanh => a.mapv(|v| 1.0 - v * v),
+        }
+    }
+
+    /// Standard deviation of the initial weights of a layer with `n_input`
+    /// inputs, He initialization for ReLU and Xavier otherwise
+    pub(crate) fn init_std(self, n_input: usize) -> f64 {
+        let n_input = n_input.max(1) as f64;
+        match self {
+            Activation::Relu => (2.0 / n_input).sqrt(),
+            Activation::Sigmoid | Activation::Tanh => (1.0 / n_input).sqrt(),
+        }
+    }
+}
+
+/// Softmax of each row, shifted by the row maximum to avoid overflow
+pub(crate) fn softmax(z: &mut Array2<f64>) {
+    for mut row in z.axis_iter_mut(Axis(0)) {
+        let max = row.fold(f64::NEG_INFINITY, |max, &v| max.max(v));
+        row.mapv_inplace(|v| (v - max).exp());
+        let sum = row.sum();
+        row /= sum;
+    }
+}
+
+/// Sum over the rows of the cross-entropy between predicted probabilities
+/// and one-hot targets
+pub(crate) fn cross_entropy(
+    probabilities: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    targets: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+) -> f64 {
+    -(targets * &probabilities.mapv(|p| p.max(1e-12).ln())).sum()
+}
@@ -0,0 +1,426 @@
+//! A model chosen at runtime by name, so the frontend can create any model
+//! the same way
+use crate::dataset::Dataset;
+use crate::models::{
+    check_data_size, check_target_size, Activation, ConvolutionalNeuralNetwork, KMeans,
+    KNearestNeighbors, Model, MultiLayerPerceptron, Perceptron, SoftmaxRegression,
+};
+use crate::preprocess::{NormalizeError, Normalizer, IMAGE_SIZE};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+use wasm_bindgen::prelude::*;
+
+fn data_size() -> usize {
+    IMAGE_SIZE * IMAGE_SIZE
+}
+
+fn n_classes() -> usize {
+    10
+}
+
+#[derive(Deserialize)]
+struct KMeansParam {
+    n_clusters: usize,
+    #[serde(default = "data_size")]
+    n_features: usize,
+}
+
+#[derive(Deserialize)]
+struct KNearestNeighborsParam {
+    k: usize,
+}
+
+#[derive(Deserialize)]
+struct PerceptronParam {
+    learning_rate: f64,
+    #[serde(default = "data_size")]
+    n_input: usize,
+    #[serde(default = "n_classes")]
+    n_output: usize,
+}
+
+#[derive(Deserialize)]
+struct MultiLayerPerceptronParam {
+    learning_rate: f64,
+    hidden_layers: Vec<usize>,
+    #[serde(default)]
+    activation: Activation,
+    #[serde(default = "data_size")]
+    n_input: usize,
+    #[serde(default = "n_classes")]
+    n_output: usize,
+    #[serde(default)]
+    seed: u64,
+}
+
+#[derive(Deserialize)]
+struct ConvolutionalNeuralNetworkParam {
+    learning_rate: f64,
+    filters: Vec<usize>,
+    hidden_layers: Vec<usize>,
+    #[serde(default)]
+    activation: Activation,
+    #[serde(default = "n_classes")]
+    n_output: usize,
+    #[serde(default)]
+    seed: u64,
+}
+
+#[derive(Deserialize)]
+struct SoftmaxRegressionParam {
+    learning_rate: f64,
+    #[serde(default)]
+    l2: f64,
+    #[serde(default = "data_size")]
+    n_input: usize,
+    #[serde(default = "n_classes")]
+    n_output: usize,
+}
+
+/// Hyperparameters of a model, checked when building it
+trait Param: for<'de> Deserialize<'de> {
+    type Model;
+    fn build(self) -> Result<Self::Model, ModelError>;
+}
+
+impl Param for KMeansParam {
+    type Model = KMeans;
+    fn build(self) -> Result<KMeans, ModelError> {
+        check(self.n_clusters > 0, "n_clusters", "at least 1")?;
+        check(self.n_features > 0, "n_features", "at least 1")?;
+        Ok(KMeans::new(self.n_clusters, self.n_features))
+    }
+}
+
+impl Param for KNearestNeighborsParam {
+    type Model = KNearestNeighbors;
+    fn build(self) -> Result<KNearestNeighbors, ModelError> {
+        check(self.k > 0, "k", "at least 1")?;
+        Ok(KNearestNeighbors::new(self.k))
+    }
+}
+
+impl Param for PerceptronParam {
+    type Model = Perceptron;
+    fn build(self) -> Result<Perceptron, ModelError> {
+        check_learning_rate(self.learning_rate)?;
+        check_sizes(self.n_input, self.n_output)?;
+        Ok(Perceptron::new(
+            self.learning_rate,
+            self.n_input,
+            self.n_output,
+        ))
+    }
+}
+
+impl Param for MultiLayerPerceptronParam {
+    type Model = MultiLayerPerceptron;
+    fn build(self) -> Result<MultiLayerPerceptron, ModelError> {
+        check_learning_rate(self.learning_rate)?;
+        check_layers(&self.hidden_layers, "hidden_layers")?;
+        check_sizes(self.n_input, self.n_output)?;
+        Ok(MultiLayerPerceptron::new(
+            self.learning_rate,
+            self.n_input,
+            self.hidden_layers,
+            self.n_output,
+            self.activation,
+            self.seed,
+        ))
+    }
+}
+
+impl Param for ConvolutionalNeuralNetworkParam {
+    type Model = ConvolutionalNeuralNetwork;
+    fn build(self) -> Result<ConvolutionalNeuralNetwork, ModelError> {
+        check_learning_rate(self.learning_rate)?;
+        check_layers(&self.filters, "filters")?;
+        check(
+            self.hidden_layers.iter().all(|&n| n > 0),
+            "hidden_layers",
+            "sizes of at least 1",
+        )?;
+        check(self.n_output > 0, "n_output", "at least 1")?;
+        ConvolutionalNeuralNetwork::new(
+            self.learning_rate,
+            self.filters,
+            self.hidden_layers,
+            self.n_output,
+            self.activation,
+            self.seed,
+        )
+    }
+}
+
+impl Param for SoftmaxRegressionParam {
+    type Model = SoftmaxRegression;
+    fn build(self) -> Result<SoftmaxRegression, ModelError> {
+        check_learning_rate(self.learning_rate)?;
+        check(self.l2 >= 0.0, "l2", "a number of at least 0")?;
+        check_sizes(self.n_input, self.n_output)?;
+        Ok(SoftmaxRegression::new(
+            self.learning_rate,
+            self.l2,
+            self.n_input,
+            self.n_output,
+        ))
+    }
+}
+
+/// Generate `MODEL_NAMES` and `Inner` from the `name => Model(Param)` table,
+/// so adding a model is a single line
+macro_rules! models {
+    ($($name:literal => $model:ident($param:ident)),* $(,)?) => {
+        /// Names accepted by `AnyModel::new`
+        const MODEL_NAMES: &[&str] = &[$($name),*];
+
+        /// The wrapped model, tagged with its name in JSON
+        #[derive(Serialize, Deserialize)]
+        #[serde(tag = "name", content = "model")]
+        enum Inner {
+            $(
+                #[serde(rename = $name)]
+                $model($model),
+            )*
+        }
+
+        impl Inner {
+            fn from_param(name: &str, param: &str) -> Result<Inner, ModelError> {
+                match name {
+                    $(
+                        $name => {
+                            let param: $param =
+                                serde_json::from_str(param).map_err(ModelError::Param)?;
+                            Ok(Inner::$model(param.build()?))
+                        }
+                    )*
+                    _ => Err(ModelError::UnknownModel(name.to_string())),
+                }
+            }
+
+            fn name(&self) -> &'static str {
+                match self {
+                    $(Inner::$model(_) => $name,)*
+                }
+            }
+
+            fn model(&self) -> &dyn Model {
+                match self {
+                    $(Inner::$model(m) => m,)*
+                }
+            }
+
+            fn model_mut(&mut self) -> &mut dyn Model {
+                match self {
+                    $(Inner::$model(m) => m,)*
+                }
+            }
+
+            fn set_normalizer(&mut self, normalizer: &Normalizer) {
+                match self {
+                    $(Inner::$model(m) => m.set_normalizer(normalizer),)*
+                }
+            }
+
+            fn predict_proba_raw(&self, observation: Vec<f64>) -> Result<Vec<f64>, ModelError> {
+                match self {
+                    $(Inner::$model(m) => m.predict_proba_raw(observation),)*
+                }
+            }
+
+            fn predict_raw(&self, observation: Vec<f64>) -> Result<usize, ModelError> {
+                match self {
+                    $(Inner::$model(m) => m.predict_raw(observation),)*
+                }
+            }
+        }
+    };
+}
+
+models! {
+    "kmeans" => KMeans(KMeansParam),
+    "knn" => KNearestNeighbors(KNearestNeighborsParam),
+    "perceptron" => Perceptron(PerceptronParam),
+    "mlp" => MultiLayerPerceptron(MultiLayerPerceptronParam),
+    "cnn" => ConvolutionalNeuralNetwork(ConvolutionalNeuralNetworkParam),
+    "softmax" => SoftmaxRegression(SoftmaxRegressionParam),
+}
+
+/// The normalizer is the wrapped model's, so it's saved along with it
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AnyModel {
+    model: Inner,
+}
+
+#[wasm_bindgen]
+impl AnyModel {
+    /// Model `name` created from a JSON object of its hyperparameters, unknown
+    /// fields are ignored and the input and output sizes default to MNIST
+    #[wasm_bindgen(constructor)]
+    pub fn new(name: &str, param: &str) -> Result<AnyModel, JsError> {
+        Ok(Self::from_param(name, param)?)
+    }
+
+    pub fn model_names() -> Vec<JsValue> {
+        MODEL_NAMES.iter().map(|&name| name.into()).collect()
+    }
+
+    /// Name the model was created with
+    pub fn name(&self) -> String {
+        self.model.name().to_string()
+    }
+
+    #[wasm_bindgen(js_name = step)]
+    pub fn js_step(&mut self, dataset: &Dataset) -> Result<f64, JsError> {
+        Ok(self.step(dataset)?)
+    }
+
+    #[wasm_bindgen(js_name = evaluate)]
+    pub fn js_evaluate(&self, dataset: &Dataset) -> Result<f64, JsError> {
+        Ok(self.evaluate(dataset)?)
+    }
+
+    #[wasm_bindgen(js_name = predict_proba)]
+    pub fn js_predict_proba(&self, observation: Vec<f64>) -> Result<Vec<f64>, JsError> {
+        Ok(self.predict_proba_raw(observation)?)
+    }
+
+    #[wasm_bindgen(js_name = predict)]
+    pub fn js_predict(&self, observation: Vec<f64>) -> Result<usize, JsError> {
+        Ok(self.predict_raw(observation)?)
+    }
+
+    /// Normalize `predict*` inputs the same way the training dataset was
+    pub fn set_normalizer(&mut self, normalizer: &Normalizer) {
+        self.model.set_normalizer(normalizer)
+    }
+}
+
+impl_json!(AnyModel);
+
+impl AnyModel {
+    pub fn from_param(name: &str, param: &str) -> Result<AnyModel, ModelError> {
+        Ok(Self {
+            model: Inner::from_param(name, param)?,
+        })
+    }
+
+    pub fn step(&mut self, dataset: &Dataset) -> Result<f64, ModelError> {
+        check_data_size(self.model(), dataset.data_size())?;
+        check_target_size(self.model(), dataset.target_size())?;
+        Ok(self.model.model_mut().step(dataset))
+    }
+
+    pub fn evaluate(&self, dataset: &Dataset) -> Result<f64, ModelError> {
+        check_data_size(self.model(), dataset.data_size())?;
+        check_target_size(self.model(), dataset.target_size())?;
+        Ok(self.model().evaluate(dataset))
+    }
+
+    /// Class probabilities of an observation before normalization
+    pub fn predict_proba_raw(&self, observation: Vec<f64>) -> Result<Vec<f64>, ModelError> {
+        self.model.predict_proba_raw(observation)
+    }
+
+    /// Most likely class of an observation before normalization
+    pub fn predict_raw(&self, observation: Vec<f64>) -> Result<usize, ModelError> {
+        self.model.predict_raw(observation)
+    }
+
+    pub fn model(&self) -> &dyn Model {
+        self.model.model()
+    }
+}
+
+fn check(valid: bool, param: &'static str, expected: &'static str) -> Result<(), ModelError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(ModelError::InvalidParam { param, expected })
+    }
+}
+
+/// NaN fails the comparison too
+fn check_learning_rate(learning_rate: f64) -> Result<(), ModelError> {
+    check(
+        learning_rate > 0.0 && learning_rate.is_finite(),
+        "learning_rate",
+        "a positive number",
+    )
+}
+
+fn check_sizes(n_input: usize, n_output: usize) -> Result<(), ModelError> {
+    check(n_input > 0, "n_input", "at least 1")?;
+    check(n_output > 0, "n_output", "at least 1")
+}
+
+fn check_layers(layers: &[usize], param: &'static str) -> Result<(), ModelError> {
+    check(
+        !layers.is_empty() && layers.iter().all(|&n| n > 0),
+        param,
+        "at least one layer of size at least 1",
+    )
+}
+
+#[derive(Debug)]
+pub enum ModelError {
+    UnknownModel(String),
+    /// Hyperparameters aren't a JSON object with the model's fields
+    Param(serde_json::Error),
+    /// Hyperparameter is out of its valid range
+    InvalidParam {
+        param: &'static str,
+        expected: &'static str,
+    },
+    /// Observations differ in size from the ones the model takes
+    DataSize {
+        expected: usize,
+        actual: usize,
+    },
+    /// Dataset has another number of classes than the model predicts
+    TargetSize {
+        expected: usize,
+        actual: usize,
+    },
+    /// Observation can't be normalized like the training dataset
+    Normalize(NormalizeError),
+}
+impl Error for ModelError {}
+
+impl Display for ModelError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            ModelError::UnknownModel(name) => {
+                write!(
+                    f,
+                    "Unknown model {:?}, expected one of {:?}",
+                    name, MODEL_NAMES
+                )
+            }
+            ModelError::Param(e) => e.fmt(f),
+            ModelError::InvalidParam { param, expected } => {
+                write!(f, "Invalid {}, expected {}", param, expected)
+            }
+            ModelError::DataSize { expected, actual } => {
+                write!(
+                    f,
+                    "Model takes observations of size {} but got {}",
+                    expected, actual
+                )
+            }
+            ModelError::TargetSize { expected, actual } => {
+                write!(
+                    f,
+                    "Model predicts {} classes but the dataset has {}",
+                    expected, actual
+                )
+            }
+            ModelError::Normalize(e) => e.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! 2D convolution of channels-last images, computed as the product of the
+//! image patches (im2col) and the kernels
+use ndarray::{Array1, Array2, Array4, Axis};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::{rand_distr::Normal, RandomExt};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub(super) struct Conv2d {
+    /// Shape: (kernel_size * kernel_size * n_channels, n_filters), rows in
+    /// (y, x, channel) order
+    kernels: Array2<f64>,
+    /// Shape: (n_filters)
+    biases: Array1<f64>,
+    kernel_size: usize,
+    /// Zeros added around the image, kernel_size / 2 keeps its size
+    padding: usize,
+}
+
+impl Conv2d {
+    pub(super) fn new(
+        n_channels: usize,
+        n_filters: usize,
+        kernel_size: usize,
+        std: f64,
+        rng: &mut StdRng,
+    ) -> Self {
+        let normal = Normal::new(0.0, std).unwrap();
+        Self {
+            kernels: Array2::random_using(
+                (kernel_size * kernel_size * n_channels, n_filters),
+                normal,
+                rng,
+            ),
+            biases: Array1::zeros(n_filters),
+            kernel_size,
+            padding: kernel_size / 2,
+        }
+    }
+
+    /// (n, height, width, n_channels) -> (patches, (n, height, width, n_filters))
+    ///
+    /// The patches are kept to compute the gradient of the kernels.
+    pub(super) fn forward(&self, input: &Array4<f64>) -> (Array2<f64>, Array4<f64>) {
+        let (n, height, width, _) = input.dim();
+        let (out_height, out_width) = self.output_size(height, width);
+
+        let patches = im2col(input, self.kernel_size, self.padding);
+        let output = patches.dot(&self.kernels) + &self.biases;
+        let output = output
+            .into_shape((n, out_height, out_width, self.biases.len()))
+            .unwrap();
+        (patches, output)
+    }
+
+    /// Gradient descent from the gradient with respect to the output, returns
+    /// the gradient with respect to an input of shape `input_dim` if given
+    pub(super) fn backward(
+        &mut self,
+        patches: &Array2<f64>,
+        delta: Array4<f64>,
+        input_dim: Option<(usize, usize, usize, usize)>,
+        learning_rate: f64,
+    ) -> Option<Array4<f64>> {
+        let delta = delta
+            .into_shape((patches.nrows(), self.biases.len()))
+            .unwrap();
+        // propagated with the kernels before their update
+        let input_gradient = input_dim.map(|dim| {
+            col2im(
+                &delta.dot(&self.kernels.t()),
+                dim,
+                self.kernel_size,
+                self.padding,
+            )
+        });
+
+        self.kernels
+            .scaled_add(-learning_rate, &patches.t().dot(&delta));
+        self.biases
+            .scaled_add(-learning_rate, &delta.sum_axis(Axis(0)));
+        input_gradient
+    }
+
+    fn output_size(&self, height: usize, width: usize) -> (usize, usize) {
+        (
+            height + 2 * self.padding + 1 - self.kernel_size,
+            width + 2 * self.padding + 1 - self.kernel_size,
+        )
+    }
+}
+
+/// (n, height, width, n_channels) -> (n * out_height * out_width, k * k * n_channels),
+/// one row per output pixel holding the input patch under the kernel
+fn im2col(input: &Array4<f64>, kernel_size: usize, padding: usize) -> Array2<f64> {
+    let (n, height, width, n_channels) = input.dim();
+    let out_height = height + 2 * padding + 1 - kernel_size;
+    let out_width = width + 2 * padding + 1 - kernel_size;
+    let row_len = kernel_size * kernel_size * n_channels;
+
+    let input = input.as_standard_layout();
+    let input = input.as_slice().unwrap();
+    let mut patches = Array2::zeros((n * out_height * out_width, row_len));
+    let data = patches.as_slice_mut().unwrap();
+
+    for_each_patch(
+        (n, height, width, n_channels),
+        kernel_size,
+        padding,
+        |src, dst| data[dst..dst + n_channels].copy_from_slice(&input[src..src + n_channels]),
+    );
+    patches
+}
+
+/// Inverse of `im2col`, the values of overlapping patches are summed
+fn col2im(
+    patches: &Array2<f64>,
+    dim: (usize, usize, usize, usize),
+    kernel_size: usize,
+    padding: usize,
+) -> Array4<f64> {
+    let n_channels = dim.3;
+    let patches = patches.as_standard_layout();
+    let patches = patches.as_slice().unwrap();
+    let mut image = Array4::zeros(dim);
+    let data = image.as_slice_mut().unwrap();
+
+    for_each_patch(dim, kernel_size, padding, |src, dst| {
+        data[src..src + n_channels]
+            .iter_mut()
+            .zip(&patches[dst..dst + n_channels])
+            .for_each(|(d, p)| *d += p);
+    });
+    image
+}
+
+/// Calls `f(src, dst)` with the offsets of the channels of every pixel under
+/// every kernel position, `src` in the image and `dst` in the patches
+fn for_each_patch(
+    (n, height, width, n_channels): (usize, usize, usize, usize),
+    kernel_size: usize,
+    padding: usize,
+    mut f: impl FnMut(usize, usize),
+) {
+    let out_height = height + 2 * padding + 1 - kernel_size;
+    let out_width = width + 2 * padding + 1 - kernel_size;
+    let row_len = kernel_size * kernel_size * n_channels;
+
+    for b in 0..n {
+        for y in 0..out_height {
+            for x in 0..out_width {
+                let row = ((b * out_height + y) * out_width + x) * row_len;
+                for ky in 0..kernel_size {
+                    // wraps around for the padding above the image
+                    let iy = (y + ky).wrapping_sub(padding);
+                    if iy >= height {
+                        continue;
+                    }
+                    for kx in 0..kernel_size {
+                        let ix = (x + kx).wrapping_sub(padding);
+                        if ix >= width {
+                            continue;
+                        }
+                        let src = ((b * height + iy) * width + ix) * n_channels;
+                        f(src, row + (ky * kernel_size + kx) * n_channels);
+                    }
+                }
+            }
+        }
+    }
+}
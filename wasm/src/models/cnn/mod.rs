@@ -0,0 +1,285 @@
+//! Convolutional neural network of convolution blocks followed by fully
+//! connected layers
+//!
+//! - LeCun et al., "Gradient-Based Learning Applied to Document Recognition", 1998
+//!
+//! Each convolution block is a 5x5 convolution keeping the image size, the
+//! activation and a 2x2 max pooling. The pooled features are flattened into
+//! fully connected layers ending with a softmax. Unlike the valid convolutions
+//! of LeNet-5, only the pooling shrinks the image, e.g. 28 -> 14 -> 7 for
+//! filters [6, 16], so the first hidden layer sees 7x7x16 features.
+mod conv;
+mod pool;
+
+use crate::dataset::{Dataset, DatasetView};
+use crate::models::activation::{cross_entropy, Activation};
+use crate::models::{count_errors, predict_chunks, Model, ModelError, MultiLayerPerceptron};
+use crate::preprocess::{Normalizer, IMAGE_SIZE};
+use conv::Conv2d;
+use ndarray::{s, Array1, Array2, Array4, ArrayBase, ArrayView1, Axis, Data, Ix1, Ix2};
+use ndarray_rand::rand::{rngs::StdRng, SeedableRng};
+use pool::{max_pool, max_pool_backward};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+const KERNEL_SIZE: usize = 5;
+const POOL_SIZE: usize = 2;
+/// Number of observations fed forward at a time, the convolution patches copy
+/// 25 values per pixel, 157 kB per 28x28 observation and input channel
+const CHUNK_SIZE: usize = 64;
+/// Pooling shrinks the image 28 -> 14 -> 7 -> 3 -> 1, a fifth block would
+/// leave no features
+const MAX_CONV_BLOCKS: usize = 4;
+
+/// Intermediate results of a batch needed for backpropagation
+struct Forward {
+    /// Input patches of each convolution
+    patches: Vec<Array2<f64>>,
+    /// Shape: (n, height, width, n_filters), activated output of each
+    /// convolution before pooling
+    activated: Vec<Array4<f64>>,
+    /// Output of every dense layer, see `MultiLayerPerceptron`
+    dense: Vec<Array2<f64>>,
+}
+
+/// Observations are 28x28 images, i.e. a data_size of 784
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
+pub struct ConvolutionalNeuralNetwork {
+    learning_rate: f64,
+    activation: Activation,
+    conv: Vec<Conv2d>,
+    dense: MultiLayerPerceptron,
+    normalizer: Option<Normalizer>,
+}
+
+#[wasm_bindgen]
+impl ConvolutionalNeuralNetwork {
+    #[wasm_bindgen(constructor)]
+    pub fn js_new(
+        learning_rate: f64,
+        filters: Vec<usize>,
+        hidden_layers: Vec<usize>,
+        n_output: usize,
+        activation: Activation,
+        seed: u64,
+    ) -> Result<ConvolutionalNeuralNetwork, JsError> {
+        Ok(Self::new(
+            learning_rate,
+            filters,
+            hidden_layers,
+            n_output,
+            activation,
+            seed,
+        )?)
+    }
+
+    /// One step of gradient descent on each batch of 64 consecutive
+    /// observations, a single step for a mini-batch from `Batches` no larger
+    /// than that, returns the error rate before the updates
+    pub fn step(&mut self, dataset: &Dataset) -> f64 {
+        let mut n_error = 0;
+        for batch in dataset.batches(CHUNK_SIZE, false, false, 0) {
+            n_error += self.train(&batch.observations(), &batch.targets(), &batch.labels());
+        }
+        n_error as f64 / dataset.n_observations() as f64
+    }
+
+    pub fn evaluate(&self, dataset: &Dataset) -> f64 {
+        let mut n_error = 0;
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            let output = self.output(&observations);
+            n_error += count_errors(&output, &dataset.labels().slice(s![range]));
+        }
+        n_error as f64 / dataset.n_observations() as f64
+    }
+
+    /// Mean cross-entropy of the predictions
+    pub fn loss(&self, dataset: &Dataset) -> f64 {
+        let mut loss = 0.0;
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            let output = self.output(&observations);
+            loss += cross_entropy(&output, &dataset.targets().slice(s![range, ..]));
+        }
+        loss / dataset.n_observations() as f64
+    }
+}
+
+impl_normalized_model!(ConvolutionalNeuralNetwork);
+
+impl ConvolutionalNeuralNetwork {
+    /// - filters: number of filters of each convolution block, at most 4 blocks
+    /// - hidden_layers: number of units of each hidden dense layer
+    /// - seed: of the initial weights
+    pub fn new(
+        learning_rate: f64,
+        filters: Vec<usize>,
+        hidden_layers: Vec<usize>,
+        n_output: usize,
+        activation: Activation,
+        seed: u64,
+    ) -> Result<Self, ModelError> {
+        if filters.len() > MAX_CONV_BLOCKS {
+            return Err(ModelError::InvalidParam {
+                param: "filters",
+                expected: "at most 4 layers, each halves the size of the image",
+            });
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (mut size, mut n_channels) = (IMAGE_SIZE, 1);
+        let mut conv = Vec::with_capacity(filters.len());
+        for n_filters in filters {
+            let std = activation.init_std(KERNEL_SIZE * KERNEL_SIZE * n_channels);
+            conv.push(Conv2d::new(
+                n_channels,
+                n_filters,
+                KERNEL_SIZE,
+                std,
+                &mut rng,
+            ));
+            size /= POOL_SIZE;
+            n_channels = n_filters;
+        }
+
+        let dense = MultiLayerPerceptron::new(
+            learning_rate,
+            size * size * n_channels,
+            hidden_layers,
+            n_output,
+            activation,
+            seed.wrapping_add(1),
+        );
+
+        Ok(Self {
+            learning_rate,
+            activation,
+            conv,
+            dense,
+            normalizer: None,
+        })
+    }
+
+    /// Returns the number of errors before the update
+    fn train(
+        &mut self,
+        observations: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+        targets: &Array2<f64>,
+        labels: &ArrayBase<impl Data<Elem = usize>, Ix1>,
+    ) -> usize {
+        let forward = self.feed_forward(observations);
+        let n_error = count_errors(forward.dense.last().unwrap(), labels);
+        self.backpropagate(forward, targets);
+        n_error
+    }
+
+    fn feed_forward(
+        &self,
+        // shape: (n_observations, IMAGE_SIZE * IMAGE_SIZE)
+        input: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    ) -> Forward {
+        let n = input.nrows();
+        let mut x = input
+            .to_owned()
+            .into_shape((n, IMAGE_SIZE, IMAGE_SIZE, 1))
+            .unwrap();
+
+        let mut patches = Vec::with_capacity(self.conv.len());
+        let mut activated = Vec::with_capacity(self.conv.len());
+        for conv in &self.conv {
+            let (p, mut a) = conv.forward(&x);
+            self.activation.apply(&mut a);
+            x = max_pool(&a, POOL_SIZE);
+            patches.push(p);
+            activated.push(a);
+        }
+
+        let (_, height, width, n_channels) = x.dim();
+        let x = x.into_shape((n, height * width * n_channels)).unwrap();
+        Forward {
+            patches,
+            activated,
+            dense: self.dense.feed_forward(&x),
+        }
+    }
+
+    /// Shape: (n_observations, n_output), class probabilities
+    fn output(&self, input: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Array2<f64> {
+        self.feed_forward(input).dense.pop().unwrap()
+    }
+
+    /// Gradient descent on the mean cross-entropy of a batch
+    fn backpropagate(&mut self, forward: Forward, targets: &Array2<f64>) {
+        let Forward {
+            patches,
+            activated,
+            dense,
+        } = forward;
+        let delta = self
+            .dense
+            .backpropagate(&dense, targets, !self.conv.is_empty());
+        let mut delta = match delta {
+            Some(delta) => delta.into_dyn(),
+            None => return,
+        };
+
+        for l in (0..self.conv.len()).rev() {
+            // the dense and convolution layers are stored channels-last, so
+            // the flattened gradient only needs reshaping
+            let (n, height, width, n_filters) = activated[l].dim();
+            let pooled_dim = (n, height / POOL_SIZE, width / POOL_SIZE, n_filters);
+            let pooled_delta = delta.into_shape(pooled_dim).unwrap();
+
+            let delta_z = max_pool_backward(&activated[l], &pooled_delta, POOL_SIZE)
+                * self.activation.derivative(&activated[l]);
+            let input_dim = match l {
+                0 => None,
+                _ => {
+                    let (n, height, width, n_channels) = activated[l - 1].dim();
+                    Some((n, height / POOL_SIZE, width / POOL_SIZE, n_channels))
+                }
+            };
+
+            match self.conv[l].backward(&patches[l], delta_z, input_dim, self.learning_rate) {
+                Some(input_delta) => delta = input_delta.into_dyn(),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Panics on observations that aren't 28x28, like the other models on
+/// observations of another size, see `data_size`
+impl Model for ConvolutionalNeuralNetwork {
+    fn step(&mut self, dataset: &Dataset) -> f64 {
+        self.step(dataset)
+    }
+
+    fn step_batch(&mut self, batch: &DatasetView) -> f64 {
+        let n_error = self.train(&batch.observations(), &batch.targets(), &batch.labels());
+        n_error as f64 / batch.n_observations() as f64
+    }
+
+    fn evaluate(&self, dataset: &Dataset) -> f64 {
+        self.evaluate(dataset)
+    }
+
+    fn data_size(&self) -> Option<usize> {
+        Some(IMAGE_SIZE * IMAGE_SIZE)
+    }
+
+    fn n_classes(&self) -> Option<usize> {
+        Model::n_classes(&self.dense)
+    }
+
+    fn predict_proba(&self, observation: ArrayView1<f64>) -> Array1<f64> {
+        self.output(&observation.insert_axis(Axis(0)))
+            .remove_axis(Axis(0))
+    }
+
+    fn predict_batch(&self, dataset: &Dataset) -> Array1<usize> {
+        predict_chunks(dataset, CHUNK_SIZE, |observations| {
+            self.output(&observations)
+        })
+    }
+}
@@ -0,0 +1,47 @@
+//! Max pooling of channels-last images over non-overlapping windows
+use ndarray::Array4;
+
+/// (n, height, width, n_channels) -> (n, height / size, width / size, n_channels),
+/// largest value of each size x size window
+pub(super) fn max_pool(input: &Array4<f64>, size: usize) -> Array4<f64> {
+    let (n, height, width, n_channels) = input.dim();
+    Array4::from_shape_fn(
+        (n, height / size, width / size, n_channels),
+        |(b, y, x, c)| {
+            let (y, x) = window_max(input, size, (b, y, x, c));
+            input[(b, y, x, c)]
+        },
+    )
+}
+
+/// Gradient with respect to the input, each window passes its gradient to
+/// its largest value
+pub(super) fn max_pool_backward(
+    input: &Array4<f64>,
+    delta: &Array4<f64>,
+    size: usize,
+) -> Array4<f64> {
+    let mut gradient = Array4::zeros(input.dim());
+    for ((b, y, x, c), &d) in delta.indexed_iter() {
+        let (y, x) = window_max(input, size, (b, y, x, c));
+        gradient[(b, y, x, c)] += d;
+    }
+    gradient
+}
+
+/// Position in the input of the first largest value of a window
+fn window_max(
+    input: &Array4<f64>,
+    size: usize,
+    (b, y, x, c): (usize, usize, usize, usize),
+) -> (usize, usize) {
+    let mut max = (y * size, x * size);
+    for wy in y * size..(y + 1) * size {
+        for wx in x * size..(x + 1) * size {
+            if input[(b, wy, wx, c)] > input[(b, max.0, max.1, c)] {
+                max = (wy, wx);
+            }
+        }
+    }
+    max
+}
@@ -13,7 +13,7 @@ pub fn update_membership(
     Zip::from(memberships)
         .and(observations.axis_iter(Axis(0)))
         .par_for_each(|membership, observation| {
-            *membership = find_nearest_centroid(&centroids, &observation).0;
+            *membership = find_nearest_centroid(centroids, &observation).0;
         });
 }
 
@@ -53,17 +53,18 @@ pub fn find_nearest_centroid(
 /// problem with empty clusters
 /// - https://docs.rs/linfa-clustering/latest/linfa_clustering/struct.KMeans.html
 /// - https://www.researchgate.net/publication/228414762_A_Modified_k-means_Algorithm_to_Avoid_Empty_Clusters
-pub fn update_centroids(
+pub fn update_centroids<S: Data<Elem = f64>>(
     centroids: &mut ArrayBase<impl DataMut<Elem = f64>, Ix2>,
-    observations: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    // each item has shape: (n_features)
+    observations: impl Iterator<Item = ArrayBase<S, Ix1>>,
     memberships: &ArrayBase<impl Data<Elem = usize>, Ix1>,
 ) {
     // let mut centroids = prev_centroids.clone();
     let mut counts: Array1<usize> = Array1::ones(centroids.nrows());
 
-    Zip::from(observations.outer_iter())
-        .and(memberships)
-        .for_each(|image, &centroid_i| {
+    observations
+        .zip(memberships)
+        .for_each(|(image, &centroid_i)| {
             let mut centroid = centroids.row_mut(centroid_i);
             centroid += &image;
             counts[centroid_i] += 1;
@@ -91,40 +92,25 @@ pub fn calculate_centroids_info(
         });
 }
 
-pub fn calculate_centroids_label(
-    // shape: (n_clusters), data: label or None
-    centroids_label: &mut ArrayBase<impl DataMut<Elem = Option<usize>>, Ix1>,
-    // shape: (n_clusters, 10)
-    centroids_info: &ArrayBase<impl Data<Elem = usize>, Ix2>,
-) {
-    Zip::from(centroids_label)
-        .and(centroids_info.axis_iter(Axis(0)))
-        .for_each(|label, row| {
-            let (l, n) = row.indexed_iter().max_by_key(|&(_, &num)| num).unwrap();
-            if *n == 0 {
-                *label = None;
-            } else {
-                *label = Some(l);
-            }
-        });
-}
-
+/// Share of predictions that differ from the labels
 pub fn calculate_error_rate(
-    // shape: (n_clusters), data: label or None
-    centroids_label: &ArrayBase<impl Data<Elem = Option<usize>>, Ix1>,
     // shape: (n_observations)
-    memberships: &ArrayBase<impl Data<Elem = usize>, Ix1>,
+    predictions: &ArrayBase<impl Data<Elem = usize>, Ix1>,
     // shape: (n_observations)
     labels: &ArrayBase<impl Data<Elem = usize>, Ix1>,
 ) -> f64 {
     let err = Zip::from(labels)
-        .and(memberships)
-        .fold(0.0, |acc, &target, &predict| {
-            match centroids_label[predict] {
-                Some(predict) if predict != target => acc + 1.0,
-                _ => acc,
-            }
-        });
+        .and(predictions)
+        .fold(
+            0.0,
+            |acc, &target, &predict| {
+                if predict != target {
+                    acc + 1.0
+                } else {
+                    acc
+                }
+            },
+        );
 
     err / labels.dim() as f64
 }
@@ -7,9 +7,15 @@ pub enum KMeansInit {
 }
 
 impl KMeansInit {
-    pub fn create_centroid<Sh: ShapeBuilder<Dim = Ix2>>(&self, shape: Sh) -> Array2<f64> {
+    /// Centroids with values in `low..=high`, the range of the observations
+    pub fn create_centroid<Sh: ShapeBuilder<Dim = Ix2>>(
+        &self,
+        shape: Sh,
+        low: f64,
+        high: f64,
+    ) -> Array2<f64> {
         match self {
-            KMeansInit::Random => Array2::random(shape, Uniform::new(0.0, 255.0)),
+            KMeansInit::Random => Array2::random(shape, Uniform::new_inclusive(low, high)),
         }
     }
 }
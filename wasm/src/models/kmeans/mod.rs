@@ -2,24 +2,27 @@ mod algorithm;
 mod init;
 
 use crate::dataset::Dataset;
-use crate::models::Model;
+use crate::models::{argmax, Model, CHUNK_SIZE};
+use crate::preprocess::Normalizer;
 use algorithm::{
-    calculate_centroids_info, calculate_centroids_label, calculate_error_rate,
-    find_nearest_centroid, update_centroids, update_membership,
+    calculate_centroids_info, calculate_error_rate, find_nearest_centroid, update_centroids,
+    update_membership,
 };
 use init::KMeansInit;
-use ndarray::{Array1, Array2};
-
+use ndarray::{s, Array1, Array2, ArrayView1};
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
 pub struct KMeans {
     // Shape: (n_clusters, n_features = data_size = 28 * 28)
     centroids: Array2<f64>,
-    // Shape: (n_clusters, 10)
+    // Shape: (n_clusters, n_classes)
     centroids_info: Array2<usize>,
-    // Shape: (n_clusters), Value: (label, num_in_cluster)
-    centroids_label: Array1<Option<usize>>,
+    // Centroids are placed randomly within the range of the first dataset
+    initialized: bool,
+    normalizer: Option<Normalizer>,
 }
 
 #[wasm_bindgen]
@@ -27,46 +30,96 @@ impl KMeans {
     #[wasm_bindgen(constructor)]
     pub fn new(n_clusters: usize, n_features: usize) -> Self {
         Self {
-            centroids: KMeansInit::Random.create_centroid((n_clusters, n_features)),
+            centroids: Array2::zeros((n_clusters, n_features)),
             centroids_info: Array2::zeros((n_clusters, 10)),
-            centroids_label: Array1::from_elem(n_clusters, None),
+            initialized: false,
+            normalizer: None,
         }
     }
 
     pub fn step(&mut self, dataset: &Dataset) -> f64 {
+        if !self.initialized {
+            let (low, high) = value_range(dataset);
+            self.centroids = KMeansInit::Random.create_centroid(self.centroids.dim(), low, high);
+            self.initialized = true;
+        }
+
         // 1. Assignment Step: Assign observations to cluster with nearest centroid
-        let mut memberships = Array1::zeros(dataset.n_observations());
-        update_membership(&mut memberships, &self.centroids, dataset.observations());
+        let memberships = self.calculate_memberships(dataset);
 
         // 2. Update Step: Recalculate centroid for each cluster
-        update_centroids(&mut self.centroids, dataset.observations(), &memberships);
+        update_centroids(
+            &mut self.centroids,
+            dataset.observation_iter(),
+            &memberships,
+        );
 
         // 3. Calculate centroid info
+        if self.centroids_info.ncols() != dataset.target_size() {
+            self.centroids_info = Array2::zeros((self.centroids.nrows(), dataset.target_size()));
+        }
         calculate_centroids_info(&mut self.centroids_info, &memberships, dataset.labels());
-        calculate_centroids_label(&mut self.centroids_label, &self.centroids_info);
 
         // 4. Return error rate
-        calculate_error_rate(&self.centroids_label, &memberships, dataset.labels())
+        calculate_error_rate(&self.cluster_labels(&memberships), dataset.labels())
     }
 
     pub fn evaluate(&self, dataset: &Dataset) -> f64 {
         // 1. Assignment Step: Assign observations to cluster with nearest centroid
-        let mut memberships = Array1::zeros(dataset.n_observations());
-        update_membership(&mut memberships, &self.centroids, dataset.observations());
+        let memberships = self.calculate_memberships(dataset);
 
         // 2. Return error rate
-        calculate_error_rate(&self.centroids_label, &memberships, dataset.labels())
+        calculate_error_rate(&self.cluster_labels(&memberships), dataset.labels())
     }
+}
 
-    pub fn predict(&self, observation: Vec<f64>) -> Vec<f64> {
-        let observation = Array1::from_vec(observation);
-        let membership = find_nearest_centroid(&self.centroids, &observation).0;
+impl_normalized_model!(KMeans);
+
+impl KMeans {
+    fn calculate_memberships(&self, dataset: &Dataset) -> Array1<usize> {
+        let mut memberships = Array1::zeros(dataset.n_observations());
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            update_membership(
+                &mut memberships.slice_mut(s![range]),
+                &self.centroids,
+                &observations,
+            );
+        }
+        memberships
+    }
+
+    /// Share of each label among the training observations in the cluster,
+    /// uniform if the cluster has none
+    fn cluster_distribution(&self, membership: usize) -> Array1<f64> {
         let cluster_info = self.centroids_info.row(membership);
         let num_in_cluster = cluster_info.sum();
+        if num_in_cluster == 0 {
+            return Array1::from_elem(cluster_info.len(), 1.0 / cluster_info.len() as f64);
+        }
 
-        cluster_info
-            .map(|&v| v as f64 / num_in_cluster as f64)
-            .into_raw_vec()
+        cluster_info.mapv(|v| v as f64 / num_in_cluster as f64)
+    }
+
+    /// Label predicted for each cluster membership, the first of the most
+    /// common labels in the cluster, so 0 for a cluster without observations
+    fn cluster_labels(&self, memberships: &Array1<usize>) -> Array1<usize> {
+        memberships.mapv(|membership| argmax(&self.cluster_distribution(membership)))
+    }
+}
+
+/// (min, max) of every value in the dataset
+fn value_range(dataset: &Dataset) -> (f64, f64) {
+    let (mut low, mut high) = (f64::INFINITY, f64::NEG_INFINITY);
+    for (_, chunk) in dataset.observation_chunks(CHUNK_SIZE) {
+        for &v in chunk.iter() {
+            low = low.min(v);
+            high = high.max(v);
+        }
+    }
+    if low <= high {
+        (low, high)
+    } else {
+        (0.0, 0.0)
     }
 }
 
@@ -75,7 +128,20 @@ impl Model for KMeans {
         self.step(dataset)
     }
 
+    fn data_size(&self) -> Option<usize> {
+        Some(self.centroids.ncols())
+    }
+
     fn evaluate(&self, dataset: &Dataset) -> f64 {
         self.evaluate(dataset)
     }
+
+    fn predict_proba(&self, observation: ArrayView1<f64>) -> Array1<f64> {
+        let membership = find_nearest_centroid(&self.centroids, &observation).0;
+        self.cluster_distribution(membership)
+    }
+
+    fn predict_batch(&self, dataset: &Dataset) -> Array1<usize> {
+        self.cluster_labels(&self.calculate_memberships(dataset))
+    }
 }
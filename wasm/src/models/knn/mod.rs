@@ -1,14 +1,17 @@
 use crate::dataset::Dataset;
 
 use crate::models::Model;
-use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix1};
-use ndarray_stats::DeviationExt;
+use crate::preprocess::Normalizer;
+use ndarray::{Array1, Array2, ArrayBase, ArrayView1, Data, Ix1};
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
 pub struct KNearestNeighbors {
     k: usize,
     stored: Dataset,
+    normalizer: Option<Normalizer>,
 }
 
 #[wasm_bindgen]
@@ -18,10 +21,12 @@ impl KNearestNeighbors {
         Self {
             k,
             stored: Dataset::new(
-                Array2::zeros((0, 0)),
+                Array2::<u8>::zeros((0, 0)),
                 Array2::zeros((0, 0)),
                 Array1::zeros(0),
-            ),
+            )
+            .unwrap(),
+            normalizer: None,
         }
     }
 
@@ -34,7 +39,7 @@ impl KNearestNeighbors {
         let n_error: usize =
             dataset
                 .observation_label_iter()
-                .fold(0, |n_error, observation, &target| {
+                .fold(0, |n_error, (observation, target)| {
                     match self.calculate_prediction(&observation) {
                         (_, Some(predict)) if predict == target => n_error,
                         _ => n_error + 1,
@@ -42,26 +47,22 @@ impl KNearestNeighbors {
                 });
         n_error as f64 / dataset.n_observations() as f64
     }
-
-    pub fn predict(&self, observation: Vec<f64>) -> Vec<f64> {
-        let observation = Array1::from_vec(observation);
-        let counts = self.calculate_prediction(&observation).0;
-
-        counts.mapv(|v| v as f64 / self.k as f64).into_raw_vec()
-    }
 }
 
+impl_normalized_model!(KNearestNeighbors);
+
 impl KNearestNeighbors {
     /// predict by adding the target of k closest observations together
     pub fn calculate_prediction(
         &self,
         observation: &ArrayBase<impl Data<Elem = f64>, Ix1>,
     ) -> (Array1<usize>, Option<usize>) {
+        // squared distances sort the same
         let mut distances: Vec<(usize, f64)> = self
             .stored
-            .observations()
-            .axis_iter(Axis(0))
-            .map(|o| o.l2_dist(observation).unwrap())
+            .stored_observations()
+            .squared_distances(observation)
+            .into_iter()
             .enumerate()
             .collect();
 
@@ -69,7 +70,8 @@ impl KNearestNeighbors {
 
         let mut targets = Array1::zeros(self.stored.target_size());
         let mut predict = None;
-        for &(i, _) in &distances[..self.k] {
+        // fewer than k observations are stored early on or in small datasets
+        for &(i, _) in &distances[..self.k.min(distances.len())] {
             // targets += &self.stored.targets().row(i);
             let label = self.stored.labels()[i];
             targets[label] += 1;
@@ -89,7 +91,23 @@ impl Model for KNearestNeighbors {
         self.step(dataset)
     }
 
+    /// Size of the stored observations once a dataset is stored
+    fn data_size(&self) -> Option<usize> {
+        Some(self.stored.data_size()).filter(|_| self.stored.n_observations() > 0)
+    }
+
     fn evaluate(&self, dataset: &Dataset) -> f64 {
         self.evaluate(dataset)
     }
+
+    fn predict_proba(&self, observation: ArrayView1<f64>) -> Array1<f64> {
+        let counts = self.calculate_prediction(&observation).0;
+        let n_neighbors = self.k.min(self.stored.n_observations()).max(1);
+        counts.mapv(|v| v as f64 / n_neighbors as f64)
+    }
+
+    /// Ties are broken in favour of the nearest neighbour, as in `evaluate`
+    fn predict(&self, observation: ArrayView1<f64>) -> usize {
+        self.calculate_prediction(&observation).1.unwrap_or(0)
+    }
 }
@@ -0,0 +1,195 @@
+use crate::dataset::{Dataset, DatasetView};
+use crate::models::activation::{cross_entropy, softmax, Activation};
+use crate::models::{count_errors, predict_chunks, Model, CHUNK_SIZE};
+use crate::preprocess::Normalizer;
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix1, Ix2};
+use ndarray_rand::rand::{rngs::StdRng, SeedableRng};
+use ndarray_rand::{rand_distr::Normal, RandomExt};
+use serde::{Deserialize, Serialize};
+use std::iter::once;
+use wasm_bindgen::prelude::*;
+
+/// Fully connected network with a softmax output trained on cross-entropy
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
+pub struct MultiLayerPerceptron {
+    learning_rate: f64,
+    activation: Activation,
+    /// Shape of each layer: (1 + n_input, n_output), biases in the first row
+    layers: Vec<Array2<f64>>,
+    normalizer: Option<Normalizer>,
+}
+
+#[wasm_bindgen]
+impl MultiLayerPerceptron {
+    /// - hidden_layers: number of units of each hidden layer
+    /// - seed: of the initial weights
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        learning_rate: f64,
+        n_input: usize,
+        hidden_layers: Vec<usize>,
+        n_output: usize,
+        activation: Activation,
+        seed: u64,
+    ) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let sizes: Vec<usize> = once(n_input)
+            .chain(hidden_layers)
+            .chain(once(n_output))
+            .collect();
+        let layers = sizes
+            .windows(2)
+            .map(|size| {
+                let normal = Normal::new(0.0, activation.init_std(size[0])).unwrap();
+                let mut layer = Array2::random_using((1 + size[0], size[1]), normal, &mut rng);
+                layer.row_mut(0).fill(0.0);
+                layer
+            })
+            .collect();
+
+        Self {
+            learning_rate,
+            activation,
+            layers,
+            normalizer: None,
+        }
+    }
+
+    /// One step of gradient descent on the whole dataset, e.g. a mini-batch
+    /// from `Batches`, returns the error rate before the update
+    pub fn step(&mut self, dataset: &Dataset) -> f64 {
+        self.train(&dataset.observations(), dataset.targets(), dataset.labels())
+    }
+
+    pub fn evaluate(&self, dataset: &Dataset) -> f64 {
+        let mut n_error = 0;
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            let output = self.output(&observations);
+            n_error += count_errors(&output, &dataset.labels().slice(s![range]));
+        }
+        n_error as f64 / dataset.n_observations() as f64
+    }
+
+    /// Mean cross-entropy of the predictions
+    pub fn loss(&self, dataset: &Dataset) -> f64 {
+        let mut loss = 0.0;
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            let output = self.output(&observations);
+            loss += cross_entropy(&output, &dataset.targets().slice(s![range, ..]));
+        }
+        loss / dataset.n_observations() as f64
+    }
+}
+
+impl_normalized_model!(MultiLayerPerceptron);
+
+impl MultiLayerPerceptron {
+    /// Output of every layer, starting with the input and ending with
+    /// the class probabilities
+    ///
+    /// a(l + 1) = f(a(l) * w(l) + b(l)), with f the softmax for the last layer
+    pub(crate) fn feed_forward(
+        &self,
+        // shape: (n_observations, data_size)
+        input: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    ) -> Vec<Array2<f64>> {
+        let mut activations = vec![input.to_owned()];
+        for (l, layer) in self.layers.iter().enumerate() {
+            let (b, w) = layer.view().split_at(Axis(0), 1);
+            let mut z = activations[l].dot(&w) + b;
+            if l + 1 < self.layers.len() {
+                self.activation.apply(&mut z);
+            } else {
+                softmax(&mut z);
+            }
+            activations.push(z);
+        }
+        activations
+    }
+
+    /// Shape: (n_observations, n_output), class probabilities
+    fn output(&self, input: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Array2<f64> {
+        self.feed_forward(input).pop().unwrap()
+    }
+
+    fn train(
+        &mut self,
+        observations: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+        targets: &Array2<f64>,
+        labels: &ArrayBase<impl Data<Elem = usize>, Ix1>,
+    ) -> f64 {
+        let activations = self.feed_forward(observations);
+        let n_error = count_errors(activations.last().unwrap(), labels);
+        self.backpropagate(&activations, targets, false);
+        n_error as f64 / labels.len() as f64
+    }
+
+    /// Gradient descent on the mean cross-entropy of a batch, returns the
+    /// gradient with respect to the input when `input_gradient` is set, e.g.
+    /// to keep propagating into the layers before
+    pub(crate) fn backpropagate(
+        &mut self,
+        activations: &[Array2<f64>],
+        targets: &Array2<f64>,
+        input_gradient: bool,
+    ) -> Option<Array2<f64>> {
+        let n = targets.nrows().max(1) as f64;
+        // gradient of the loss with respect to the input of the softmax
+        let mut delta = (activations.last().unwrap() - targets) / n;
+
+        for l in (0..self.layers.len()).rev() {
+            let input = &activations[l];
+            // propagated with the weights before their update
+            let previous = (l > 0 || input_gradient).then(|| {
+                let w = self.layers[l].slice(s![1.., ..]);
+                let previous = delta.dot(&w.t());
+                if l > 0 {
+                    previous * self.activation.derivative(input)
+                } else {
+                    previous
+                }
+            });
+
+            let (mut b, mut w) = self.layers[l].view_mut().split_at(Axis(0), 1);
+            w.scaled_add(-self.learning_rate, &input.t().dot(&delta));
+            b.scaled_add(-self.learning_rate, &delta.sum_axis(Axis(0)));
+
+            delta = previous?;
+        }
+        Some(delta)
+    }
+}
+
+impl Model for MultiLayerPerceptron {
+    fn step(&mut self, dataset: &Dataset) -> f64 {
+        self.step(dataset)
+    }
+
+    fn step_batch(&mut self, batch: &DatasetView) -> f64 {
+        self.train(&batch.observations(), &batch.targets(), &batch.labels())
+    }
+
+    fn data_size(&self) -> Option<usize> {
+        Some(self.layers[0].nrows() - 1)
+    }
+
+    fn n_classes(&self) -> Option<usize> {
+        Some(self.layers.last().unwrap().ncols())
+    }
+
+    fn evaluate(&self, dataset: &Dataset) -> f64 {
+        self.evaluate(dataset)
+    }
+
+    fn predict_proba(&self, observation: ArrayView1<f64>) -> Array1<f64> {
+        self.output(&observation.insert_axis(Axis(0)))
+            .remove_axis(Axis(0))
+    }
+
+    fn predict_batch(&self, dataset: &Dataset) -> Array1<usize> {
+        predict_chunks(dataset, CHUNK_SIZE, |observations| {
+            self.output(&observations)
+        })
+    }
+}
@@ -1,49 +1,158 @@
+mod activation;
+mod any;
+mod cnn;
 mod kmeans;
 mod knn;
+mod mlp;
 mod perceptron;
+mod softmax;
 
-use crate::dataset::Dataset;
+use crate::dataset::{Dataset, DatasetView};
+use crate::preprocess::{NormalizeError, Normalizer};
 
-use wasm_bindgen::prelude::*;
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, Axis, CowArray, Data, Ix1, Ix2};
 
+pub use activation::Activation;
+pub use any::{AnyModel, ModelError};
+pub use cnn::ConvolutionalNeuralNetwork;
 pub use kmeans::KMeans;
 pub use knn::KNearestNeighbors;
+pub use mlp::MultiLayerPerceptron;
 pub use perceptron::Perceptron;
+pub use softmax::SoftmaxRegression;
 
+/// Number of observations converted to f64 at a time when processing a dataset
+pub(crate) const CHUNK_SIZE: usize = 1_000;
+
+/// Apply the normalizer the model was trained with to a `predict_proba_raw` input
+pub(crate) fn normalize(
+    normalizer: &Option<Normalizer>,
+    observation: Vec<f64>,
+) -> Result<Array1<f64>, NormalizeError> {
+    let observation = Array1::from_vec(observation);
+    match normalizer {
+        Some(normalizer) => Ok(normalizer
+            .transform_observations(&observation.insert_axis(Axis(0)))?
+            .remove_axis(Axis(0))),
+        None => Ok(observation),
+    }
+}
+
+/// Error instead of the panic of `model` on observations of `data_size` values
+pub(crate) fn check_data_size<M: Model + ?Sized>(
+    model: &M,
+    data_size: usize,
+) -> Result<(), ModelError> {
+    match model.data_size() {
+        Some(expected) if expected != data_size => Err(ModelError::DataSize {
+            expected,
+            actual: data_size,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Error instead of the panic of `model` on datasets of `target_size` classes
+pub(crate) fn check_target_size<M: Model + ?Sized>(
+    model: &M,
+    target_size: usize,
+) -> Result<(), ModelError> {
+    match model.n_classes() {
+        Some(expected) if expected != target_size => Err(ModelError::TargetSize {
+            expected,
+            actual: target_size,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Index of the largest value, the first one on ties
+fn argmax(values: &ArrayBase<impl Data<Elem = f64>, Ix1>) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .fold((0, f64::NEG_INFINITY), |(i_max, max), (i, &v)| {
+            if v > max {
+                (i, v)
+            } else {
+                (i_max, max)
+            }
+        })
+        .0
+}
+
+/// Number of rows whose most likely class isn't the label
+fn count_errors(
+    output: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    labels: &ArrayBase<impl Data<Elem = usize>, Ix1>,
+) -> usize {
+    output
+        .outer_iter()
+        .zip(labels)
+        .filter(|(o, &label)| argmax(o) != label)
+        .count()
+}
+
+/// Most likely class of each observation, from the `output` of the model on
+/// chunks of `chunk_size` observations
+fn predict_chunks(
+    dataset: &Dataset,
+    chunk_size: usize,
+    mut output: impl FnMut(CowArray<f64, Ix2>) -> Array2<f64>,
+) -> Array1<usize> {
+    let mut predictions = Array1::zeros(dataset.n_observations());
+    for (range, observations) in dataset.observation_chunks(chunk_size) {
+        predictions
+            .slice_mut(s![range])
+            .iter_mut()
+            .zip(output(observations).outer_iter())
+            .for_each(|(p, o)| *p = argmax(&o));
+    }
+    predictions
+}
+
+/// Observations given to `predict*` are in the same form as the datasets given
+/// to `step`, i.e. already normalized
 pub trait Model {
     fn step(&mut self, dataset: &Dataset) -> f64;
     fn evaluate(&self, dataset: &Dataset) -> f64;
-    // TODO: fn predict(&self);
-}
-
-#[wasm_bindgen]
-pub struct TemplateModel();
 
-#[wasm_bindgen]
-impl TemplateModel {
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        todo!()
+    /// `step` on a mini-batch, by default on a copy of its observations
+    fn step_batch(&mut self, batch: &DatasetView) -> f64 {
+        self.step(&batch.to_dataset())
     }
 
-    #[allow(unused_variables)]
-    pub fn step(&mut self, dataset: &Dataset) -> f64 {
-        todo!()
+    /// Number of values of the observations the model takes, `None` if any
+    /// size is accepted, e.g. before the first `step`
+    ///
+    /// `step`, `evaluate` and `predict*` may panic on observations of
+    /// another size.
+    fn data_size(&self) -> Option<usize> {
+        None
     }
 
-    #[allow(unused_variables)]
-    pub fn evaluate(&self, dataset: &Dataset) -> f64 {
-        todo!()
+    /// Number of classes the model predicts, `None` if it takes the classes
+    /// of the dataset it's trained on
+    ///
+    /// `step` and `evaluate` may panic on datasets with another number of
+    /// classes.
+    fn n_classes(&self) -> Option<usize> {
+        None
     }
 
-    #[allow(unused_variables)]
-    pub fn predict(&self, observation: Vec<f64>) -> Vec<f64> {
-        todo!()
+    /// Shape: (n_classes), how likely the observation is of each class
+    fn predict_proba(&self, observation: ArrayView1<f64>) -> Array1<f64>;
+
+    /// Most likely class of the observation
+    fn predict(&self, observation: ArrayView1<f64>) -> usize {
+        argmax(&self.predict_proba(observation))
     }
-}
 
-impl Default for TemplateModel {
-    fn default() -> Self {
-        Self::new()
+    /// Shape: (n_observations), most likely class of every observation
+    fn predict_batch(&self, dataset: &Dataset) -> Array1<usize> {
+        dataset
+            .observation_iter()
+            .map(|observation| self.predict(observation.view()))
+            .collect()
     }
 }
@@ -1,12 +1,17 @@
 use crate::dataset::Dataset;
-use ndarray::{Array2, ArrayBase, Axis, Data, Ix2};
+use crate::models::{predict_chunks, CHUNK_SIZE};
+use crate::preprocess::Normalizer;
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix2};
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
 pub struct Perceptron {
     learning_rate: f64,
     /// shape: (1 + n_input, n_output)
     weights: Array2<f64>,
+    normalizer: Option<Normalizer>,
 }
 
 #[wasm_bindgen]
@@ -16,12 +21,16 @@ impl Perceptron {
         Self {
             learning_rate,
             weights: Array2::zeros((1 + n_input, n_output)),
+            normalizer: None,
         }
     }
 
     pub fn step(&mut self, dataset: &Dataset) -> f64 {
         let difference = self.calculate_difference(dataset);
-        self.update_weights(&(self.learning_rate * &difference), dataset.observations());
+        let update = self.learning_rate * &difference;
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            self.update_weights(&update.slice(s![range, ..]), &observations);
+        }
         self.calculate_error(&difference) / dataset.n_observations() as f64
     }
 
@@ -29,17 +38,18 @@ impl Perceptron {
         let difference = self.calculate_difference(dataset);
         self.calculate_error(&difference) / dataset.n_observations() as f64
     }
-
-    pub fn predict(&self, observation: Vec<f64>) -> Vec<f64> {
-        let observation = Array2::from_shape_vec((1, observation.len()), observation).unwrap();
-        self.feed_forward(&observation).into_raw_vec()
-    }
 }
 
+impl_normalized_model!(Perceptron);
+
 impl Perceptron {
     fn calculate_difference(&self, dataset: &Dataset) -> Array2<f64> {
-        let output = self.feed_forward(dataset.observations());
-        dataset.targets() - &output
+        let mut difference = dataset.targets().clone();
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            let mut difference = difference.slice_mut(s![range, ..]);
+            difference -= &self.feed_forward(&observations);
+        }
+        difference
     }
 
     /// y = f(x * w + b)
@@ -88,7 +98,34 @@ impl super::Model for Perceptron {
         self.step(dataset)
     }
 
+    fn data_size(&self) -> Option<usize> {
+        Some(self.weights.nrows() - 1)
+    }
+
+    fn n_classes(&self) -> Option<usize> {
+        Some(self.weights.ncols())
+    }
+
     fn evaluate(&self, dataset: &Dataset) -> f64 {
         self.evaluate(dataset)
     }
+
+    /// The outputs that fired, shared equally, or uniform if none did
+    fn predict_proba(&self, observation: ArrayView1<f64>) -> Array1<f64> {
+        let output = self
+            .feed_forward(&observation.insert_axis(Axis(0)))
+            .remove_axis(Axis(0));
+        let n_fired = output.sum();
+        if n_fired > 0.0 {
+            output / n_fired
+        } else {
+            Array1::from_elem(output.len(), 1.0 / output.len() as f64)
+        }
+    }
+
+    fn predict_batch(&self, dataset: &Dataset) -> Array1<usize> {
+        predict_chunks(dataset, CHUNK_SIZE, |observations| {
+            self.feed_forward(&observations)
+        })
+    }
 }
@@ -0,0 +1,128 @@
+use crate::dataset::Dataset;
+use crate::models::activation::{cross_entropy, softmax};
+use crate::models::{count_errors, predict_chunks, Model, CHUNK_SIZE};
+use crate::preprocess::Normalizer;
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, Axis, Data, Ix2};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// Multinomial logistic regression, a single layer with a softmax output
+/// trained on the cross-entropy, so `predict_proba` returns class probabilities
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
+pub struct SoftmaxRegression {
+    learning_rate: f64,
+    /// Strength of the L2 penalty on the weights, biases aren't penalized
+    l2: f64,
+    /// shape: (1 + n_input, n_output)
+    weights: Array2<f64>,
+    normalizer: Option<Normalizer>,
+}
+
+#[wasm_bindgen]
+impl SoftmaxRegression {
+    #[wasm_bindgen(constructor)]
+    pub fn new(learning_rate: f64, l2: f64, n_input: usize, n_output: usize) -> Self {
+        Self {
+            learning_rate,
+            l2,
+            weights: Array2::zeros((1 + n_input, n_output)),
+            normalizer: None,
+        }
+    }
+
+    /// One step of gradient descent on the whole dataset, returns the error
+    /// rate before the update
+    pub fn step(&mut self, dataset: &Dataset) -> f64 {
+        let mut gradient = Array2::zeros(self.weights.dim());
+        let mut n_error = 0;
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            let probabilities = self.feed_forward(&observations);
+            n_error += count_errors(&probabilities, &dataset.labels().slice(s![range.clone()]));
+
+            // gradient of the cross-entropy with respect to the softmax input
+            let delta = probabilities - dataset.targets().slice(s![range, ..]);
+            let (mut b, mut w) = gradient.view_mut().split_at(Axis(0), 1);
+            w += &observations.t().dot(&delta);
+            b += &delta.sum_axis(Axis(0));
+        }
+
+        gradient /= dataset.n_observations().max(1) as f64;
+        gradient
+            .slice_mut(s![1.., ..])
+            .scaled_add(self.l2, &self.weights.slice(s![1.., ..]));
+        self.weights.scaled_add(-self.learning_rate, &gradient);
+
+        n_error as f64 / dataset.n_observations() as f64
+    }
+
+    pub fn evaluate(&self, dataset: &Dataset) -> f64 {
+        let mut n_error = 0;
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            let probabilities = self.feed_forward(&observations);
+            n_error += count_errors(&probabilities, &dataset.labels().slice(s![range]));
+        }
+        n_error as f64 / dataset.n_observations() as f64
+    }
+
+    /// Mean cross-entropy of the predictions plus the L2 penalty
+    pub fn loss(&self, dataset: &Dataset) -> f64 {
+        let mut loss = 0.0;
+        for (range, observations) in dataset.observation_chunks(CHUNK_SIZE) {
+            let probabilities = self.feed_forward(&observations);
+            loss += cross_entropy(&probabilities, &dataset.targets().slice(s![range, ..]));
+        }
+        let penalty = self.weights.slice(s![1.., ..]).mapv(|w| w * w).sum();
+        loss / dataset.n_observations() as f64 + 0.5 * self.l2 * penalty
+    }
+}
+
+impl_normalized_model!(SoftmaxRegression);
+
+impl SoftmaxRegression {
+    /// y = softmax(x * w + b)
+    ///
+    /// - w: weights (data_size, n_output)
+    /// - x: inputs (n_observation, data_size)
+    /// - b: biases (1, n_output)
+    /// - y: output (n_observations, n_output), class probabilities
+    fn feed_forward(
+        &self,
+        // shape: (n_observations, data_size)
+        input: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    ) -> Array2<f64> {
+        let (b, w) = self.weights.view().split_at(Axis(0), 1);
+        let mut y = input.dot(&w) + b;
+        softmax(&mut y);
+        y
+    }
+}
+
+impl Model for SoftmaxRegression {
+    fn step(&mut self, dataset: &Dataset) -> f64 {
+        self.step(dataset)
+    }
+
+    fn data_size(&self) -> Option<usize> {
+        Some(self.weights.nrows() - 1)
+    }
+
+    fn n_classes(&self) -> Option<usize> {
+        Some(self.weights.ncols())
+    }
+
+    fn evaluate(&self, dataset: &Dataset) -> f64 {
+        self.evaluate(dataset)
+    }
+
+    fn predict_proba(&self, observation: ArrayView1<f64>) -> Array1<f64> {
+        self.feed_forward(&observation.insert_axis(Axis(0)))
+            .remove_axis(Axis(0))
+    }
+
+    fn predict_batch(&self, dataset: &Dataset) -> Array1<usize> {
+        predict_chunks(dataset, CHUNK_SIZE, |observations| {
+            self.feed_forward(&observations)
+        })
+    }
+}
@@ -0,0 +1,275 @@
+//! Random distortions of 28x28 observations to make models robust to
+//! off-centre, rotated and thicker digits drawn on the canvas
+//!
+//! Elastic distortions follow Simard et al., "Best Practices for Convolutional
+//! Neural Networks Applied to Visual Document Analysis" (2003).
+use super::IMAGE_SIZE;
+use crate::dataset::Dataset;
+use ndarray::{Array1, Array2, ArrayBase, Axis, Data, ErrorKind, Ix1, Ix2, ShapeError};
+use ndarray_rand::rand::{rngs::StdRng, Rng, SeedableRng};
+use ndarray_rand::rand_distr::{Normal, Uniform};
+use ndarray_rand::RandomExt;
+use wasm_bindgen::prelude::*;
+
+/// Largest distortion of each kind, every augmented observation draws its own
+/// amount uniformly from -max..=max. Zero disables a distortion.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct Augmentation {
+    /// Translation in pixels
+    pub max_shift: f64,
+    /// Rotation in degrees
+    pub max_rotation: f64,
+    /// Relative zoom, 0.1 scales by 0.9..=1.1
+    pub max_scale: f64,
+    /// Horizontal shear factor
+    pub max_shear: f64,
+    /// Displacement of the elastic distortion in pixels
+    pub elastic_alpha: f64,
+    /// Smoothness of the elastic distortion in pixels
+    pub elastic_sigma: f64,
+    /// Standard deviation of the Gaussian noise added to every pixel
+    pub noise_std: f64,
+}
+
+#[wasm_bindgen]
+impl Augmentation {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        max_shift: f64,
+        max_rotation: f64,
+        max_scale: f64,
+        max_shear: f64,
+        elastic_alpha: f64,
+        elastic_sigma: f64,
+        noise_std: f64,
+    ) -> Self {
+        Self {
+            max_shift,
+            max_rotation,
+            max_scale,
+            max_shear,
+            elastic_alpha,
+            elastic_sigma,
+            noise_std,
+        }
+    }
+}
+
+impl Default for Augmentation {
+    /// Small affine distortions, no elastic distortion or noise
+    fn default() -> Self {
+        Self::new(2.0, 10.0, 0.1, 0.2, 0.0, 4.0, 0.0)
+    }
+}
+
+/// Applies an `Augmentation` with its own seeded RNG
+///
+/// Either expand a dataset once with `expand`, or `augment` every mini-batch
+/// so each epoch sees different distortions.
+#[wasm_bindgen]
+pub struct Augmenter {
+    augmentation: Augmentation,
+    rng: StdRng,
+}
+
+#[wasm_bindgen]
+impl Augmenter {
+    #[wasm_bindgen(constructor)]
+    pub fn new(augmentation: Augmentation, seed: u64) -> Self {
+        Self {
+            augmentation,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Copy of a dataset of 28x28 observations with every observation
+    /// randomly distorted
+    #[wasm_bindgen(js_name = augment)]
+    pub fn js_augment(&mut self, dataset: &Dataset) -> Result<Dataset, JsError> {
+        Ok(self.augment(dataset)?)
+    }
+
+    /// Original observations followed by `n_copies` distorted copies of each
+    #[wasm_bindgen(js_name = expand)]
+    pub fn js_expand(&mut self, dataset: &Dataset, n_copies: usize) -> Result<Dataset, JsError> {
+        Ok(self.expand(dataset, n_copies)?)
+    }
+}
+
+impl Augmenter {
+    /// Same as the JS `augment`, for native tools
+    pub fn augment(&mut self, dataset: &Dataset) -> Result<Dataset, ShapeError> {
+        check_image_size(dataset.data_size())?;
+        let mut augmented = dataset.clone();
+        self.augment_rows(&mut augmented, 0, dataset)?;
+        Ok(augmented)
+    }
+
+    /// Same as the JS `expand`, for native tools
+    pub fn expand(&mut self, dataset: &Dataset, n_copies: usize) -> Result<Dataset, ShapeError> {
+        check_image_size(dataset.data_size())?;
+        let indices: Vec<usize> = (0..n_copies + 1)
+            .flat_map(|_| 0..dataset.n_observations())
+            .collect();
+        let mut expanded = dataset.select(&indices);
+        self.augment_rows(&mut expanded, dataset.n_observations(), dataset)?;
+        Ok(expanded)
+    }
+
+    /// Distort one observation of length 28 * 28, any other length is an error
+    pub fn augment_observation(
+        &mut self,
+        observation: &ArrayBase<impl Data<Elem = f64>, Ix1>,
+    ) -> Result<Array1<f64>, ShapeError> {
+        check_image_size(observation.len())?;
+        let image = observation.to_shape((IMAGE_SIZE, IMAGE_SIZE)).unwrap();
+        let a = self.augmentation;
+
+        // output pixel -> source pixel, relative to the centre of the image
+        let inverse = self.inverse_affine();
+        let (shift_y, shift_x) = (self.uniform(a.max_shift), self.uniform(a.max_shift));
+        let (dy, dx) = self.elastic_field();
+
+        let centre = (IMAGE_SIZE as f64 - 1.0) / 2.0;
+        let mut augmented = Array2::from_shape_fn((IMAGE_SIZE, IMAGE_SIZE), |(y, x)| {
+            let oy = y as f64 - centre - shift_y;
+            let ox = x as f64 - centre - shift_x;
+            let sy = inverse[0][0] * oy + inverse[0][1] * ox + centre + dy[(y, x)];
+            let sx = inverse[1][0] * oy + inverse[1][1] * ox + centre + dx[(y, x)];
+            bilinear(&image, sy, sx)
+        });
+
+        if a.noise_std > 0.0 {
+            let noise = Normal::new(0.0, a.noise_std).unwrap();
+            augmented.mapv_inplace(|v| v + self.rng.sample(noise));
+        }
+
+        Ok(augmented.into_shape(IMAGE_SIZE * IMAGE_SIZE).unwrap())
+    }
+
+    /// Replace the observations of `target` from `start` on, one at a time in
+    /// their stored element type, with distorted copies of the observations of
+    /// `source` repeated in order
+    fn augment_rows(
+        &mut self,
+        target: &mut Dataset,
+        start: usize,
+        source: &Dataset,
+    ) -> Result<(), ShapeError> {
+        let n = source.n_observations();
+        for i in start..target.n_observations() {
+            let augmented = self.augment_observation(&source.observation(i % n))?;
+            target
+                .stored_observations_mut()
+                .set_row(i, augmented.view());
+        }
+        Ok(())
+    }
+
+    /// Inverse of rotation * scale * shear as a row-major 2x2 matrix on (y, x)
+    fn inverse_affine(&mut self) -> [[f64; 2]; 2] {
+        let a = self.augmentation;
+        let angle = self.uniform(a.max_rotation).to_radians();
+        let scale = 1.0 + self.uniform(a.max_scale);
+        let shear = self.uniform(a.max_shear);
+
+        let (sin, cos) = angle.sin_cos();
+        // forward: scale * rotation * shear (x += shear * y)
+        let m = [
+            [scale * (cos + sin * shear), scale * sin],
+            [scale * (-sin + cos * shear), scale * cos],
+        ];
+        let det = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+        [
+            [m[1][1] / det, -m[0][1] / det],
+            [-m[1][0] / det, m[0][0] / det],
+        ]
+    }
+
+    /// Random displacement (dy, dx) of every pixel, smoothed by a Gaussian
+    fn elastic_field(&mut self) -> (Array2<f64>, Array2<f64>) {
+        let a = self.augmentation;
+        let shape = (IMAGE_SIZE, IMAGE_SIZE);
+        if a.elastic_alpha <= 0.0 {
+            return (Array2::zeros(shape), Array2::zeros(shape));
+        }
+
+        let mut field = || {
+            let noise =
+                Array2::random_using(shape, Uniform::new_inclusive(-1.0, 1.0), &mut self.rng);
+            let smoothed = gaussian_blur(&noise, a.elastic_sigma);
+            // rescale so the largest displacement is alpha
+            let max = smoothed.fold(0.0_f64, |m, v| m.max(v.abs()));
+            if max > 0.0 {
+                smoothed * (a.elastic_alpha / max)
+            } else {
+                smoothed
+            }
+        };
+        (field(), field())
+    }
+
+    fn uniform(&mut self, max: f64) -> f64 {
+        if max > 0.0 {
+            self.rng.gen_range(-max..=max)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Augmentation only supports 28x28 observations
+fn check_image_size(data_size: usize) -> Result<(), ShapeError> {
+    if data_size != IMAGE_SIZE * IMAGE_SIZE {
+        return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape));
+    }
+    Ok(())
+}
+
+/// Value at a fractional position, pixels outside the image are 0
+pub(super) fn bilinear(image: &ArrayBase<impl Data<Elem = f64>, Ix2>, y: f64, x: f64) -> f64 {
+    let (y0, x0) = (y.floor(), x.floor());
+    let (fy, fx) = (y - y0, x - x0);
+    let pixel = |y: f64, x: f64| {
+        if y < 0.0 || x < 0.0 {
+            return 0.0;
+        }
+        image.get((y as usize, x as usize)).copied().unwrap_or(0.0)
+    };
+
+    (1.0 - fy) * ((1.0 - fx) * pixel(y0, x0) + fx * pixel(y0, x0 + 1.0))
+        + fy * ((1.0 - fx) * pixel(y0 + 1.0, x0) + fx * pixel(y0 + 1.0, x0 + 1.0))
+}
+
+/// Separable Gaussian blur, pixels outside the image are 0
+fn gaussian_blur(image: &Array2<f64>, sigma: f64) -> Array2<f64> {
+    if sigma <= 0.0 {
+        return image.clone();
+    }
+    let radius = (3.0 * sigma).ceil() as isize;
+    let kernel: Vec<f64> = (-radius..=radius)
+        .map(|i| (-(i * i) as f64 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f64 = kernel.iter().sum();
+
+    let blur = |image: &Array2<f64>, axis: Axis| {
+        Array2::from_shape_fn(image.dim(), |(y, x)| {
+            let mut sum = 0.0;
+            for (k, w) in (-radius..=radius).zip(&kernel) {
+                let (sy, sx) = match axis {
+                    Axis(0) => (y as isize + k, x as isize),
+                    _ => (y as isize, x as isize + k),
+                };
+                if sy >= 0 && sx >= 0 {
+                    sum += w * image
+                        .get((sy as usize, sx as usize))
+                        .copied()
+                        .unwrap_or(0.0);
+                }
+            }
+            sum / total
+        })
+    };
+    blur(&blur(image, Axis(0)), Axis(1))
+}
@@ -0,0 +1,131 @@
+//! Normalize digits the way MNIST was built
+//!
+//! - http://yann.lecun.com/exdb/mnist/: the digits were size normalized to
+//!   fit a 20x20 box preserving their aspect ratio, then centred in the 28x28
+//!   image by their centre of mass
+//! - Deskewing shears the digit so its principal axis is vertical, using the
+//!   second order moments of the pixel intensities
+//!
+//! Images here have bright ink on a black background, like observations.
+use super::{augment::bilinear, bounding_box, resize, IMAGE_SIZE};
+use crate::dataset::Dataset;
+use ndarray::{s, Array2, ArrayBase, Data, ErrorKind, Ix2, ShapeError};
+use wasm_bindgen::prelude::*;
+
+/// Side of the box the digit is scaled to fit in
+const BOX_SIZE: usize = 20;
+
+/// Fit the ink of a 28x28 observation into 20x20 and centre it by its centre
+/// of mass, optionally deskewing it afterwards
+#[wasm_bindgen]
+pub fn centre_observation(observation: Vec<f64>, deskew: bool) -> Result<Vec<f64>, JsError> {
+    let image = Array2::from_shape_vec((IMAGE_SIZE, IMAGE_SIZE), observation)?;
+    let mut centred = centre_image(&image);
+    if deskew {
+        centred = deskew_image(&centred);
+    }
+    Ok(centred.into_raw_vec())
+}
+
+/// Deskew a 28x28 observation, which also centres it by its centre of mass
+#[wasm_bindgen]
+pub fn deskew_observation(observation: Vec<f64>) -> Result<Vec<f64>, JsError> {
+    let image = Array2::from_shape_vec((IMAGE_SIZE, IMAGE_SIZE), observation)?;
+    Ok(deskew_image(&image).into_raw_vec())
+}
+
+/// Copy of a dataset of 28x28 observations with every observation deskewed
+#[wasm_bindgen(js_name = deskew_dataset)]
+pub fn js_deskew_dataset(dataset: &Dataset) -> Result<Dataset, JsError> {
+    Ok(deskew_dataset(dataset)?)
+}
+
+/// Same as the JS `deskew_dataset`, for native tools, observations keep their
+/// element type
+pub fn deskew_dataset(dataset: &Dataset) -> Result<Dataset, ShapeError> {
+    if dataset.data_size() != IMAGE_SIZE * IMAGE_SIZE {
+        return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape));
+    }
+
+    let mut deskewed = dataset.clone();
+    for (i, observation) in dataset.observation_iter().enumerate() {
+        let image = observation.to_shape((IMAGE_SIZE, IMAGE_SIZE)).unwrap();
+        let image = deskew_image(&image);
+        deskewed
+            .stored_observations_mut()
+            .set_row(i, image.into_shape(IMAGE_SIZE * IMAGE_SIZE).unwrap().view());
+    }
+    Ok(deskewed)
+}
+
+/// Scale the ink of an image of any size to fit 20x20 and paste it in a 28x28
+/// image with its centre of mass in the middle
+pub fn centre_image(image: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Array2<f64> {
+    let mut centred = Array2::zeros((IMAGE_SIZE, IMAGE_SIZE));
+    let (y, x, h, w) = match bounding_box(image, |v| v > 0.0) {
+        Some(bounds) => bounds,
+        None => return centred,
+    };
+
+    let scale = BOX_SIZE as f64 / h.max(w) as f64;
+    let height = ((h as f64 * scale).round() as usize).clamp(1, BOX_SIZE);
+    let width = ((w as f64 * scale).round() as usize).clamp(1, BOX_SIZE);
+    let digit = resize(&image.slice(s![y..y + h, x..x + w]), height, width);
+
+    // integer shift, like MNIST, clipped to the 28x28 image
+    let (cy, cx) = centre_of_mass(&digit).unwrap_or((0.0, 0.0));
+    let middle = (IMAGE_SIZE as f64 - 1.0) / 2.0;
+    let top = (middle - cy).round() as isize;
+    let left = (middle - cx).round() as isize;
+    for ((dy, dx), &v) in digit.indexed_iter() {
+        let (y, x) = (top + dy as isize, left + dx as isize);
+        if (0..IMAGE_SIZE as isize).contains(&y) && (0..IMAGE_SIZE as isize).contains(&x) {
+            centred[(y as usize, x as usize)] = v;
+        }
+    }
+    centred
+}
+
+/// Shear the image so the digit stands upright, its centre of mass is moved
+/// to the middle of the image
+pub fn deskew_image(image: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Array2<f64> {
+    let (cy, cx) = match centre_of_mass(image) {
+        Some(centre) => centre,
+        None => return image.to_owned(),
+    };
+
+    // skew = covariance(x, y) / variance(y)
+    let (mut mu11, mut mu02) = (0.0, 0.0);
+    for ((y, x), &v) in image.indexed_iter() {
+        let (dy, dx) = (y as f64 - cy, x as f64 - cx);
+        mu11 += v * dx * dy;
+        mu02 += v * dy * dy;
+    }
+    let skew = if mu02 > 0.0 { mu11 / mu02 } else { 0.0 };
+
+    // output pixel -> source pixel
+    let (my, mx) = (
+        (image.nrows() as f64 - 1.0) / 2.0,
+        (image.ncols() as f64 - 1.0) / 2.0,
+    );
+    Array2::from_shape_fn(image.dim(), |(y, x)| {
+        let dy = y as f64 - my;
+        bilinear(image, cy + dy, cx + (x as f64 - mx) + skew * dy)
+    })
+}
+
+/// Intensity weighted mean (y, x) position, None for an empty image
+pub fn centre_of_mass(image: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Option<(f64, f64)> {
+    let (mut total, mut sum_y, mut sum_x) = (0.0, 0.0, 0.0);
+    for ((y, x), &v) in image.indexed_iter() {
+        total += v;
+        sum_y += v * y as f64;
+        sum_x += v * x as f64;
+    }
+
+    if total > 0.0 {
+        Some((sum_y / total, sum_x / total))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,200 @@
+//! Eigen decomposition of a real symmetric matrix
+//!
+//! Householder reduction to tridiagonal form followed by the implicit QL
+//! algorithm, ported from the EISPACK routines tred2 and tql2 as found in JAMA
+//! - https://math.nist.gov/javanumerics/jama/
+use ndarray::{Array1, Array2, Axis};
+
+/// Eigenvalues in descending order and the matching unit eigenvectors as the
+/// columns of a (n, n) matrix
+pub(super) fn symmetric_eigen(a: Array2<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = a.nrows();
+    assert_eq!(n, a.ncols(), "matrix must be square");
+
+    let mut v = a;
+    let mut d = Array1::zeros(n);
+    let mut e = Array1::zeros(n);
+    if n > 0 {
+        tred2(&mut v, &mut d, &mut e);
+        tql2(&mut v, &mut d, &mut e);
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| d[j].total_cmp(&d[i]));
+    (d.select(Axis(0), &order), v.select(Axis(1), &order))
+}
+
+/// Householder tridiagonalization, `d` and `e` receive the diagonal and
+/// subdiagonal and `v` the accumulated orthogonal transformation
+fn tred2(v: &mut Array2<f64>, d: &mut Array1<f64>, e: &mut Array1<f64>) {
+    let n = v.nrows();
+    for j in 0..n {
+        d[j] = v[[n - 1, j]];
+    }
+
+    for i in (1..n).rev() {
+        // scale to avoid under/overflow
+        let scale: f64 = (0..i).map(|k| d[k].abs()).sum();
+        let mut h = 0.0;
+
+        if scale == 0.0 {
+            e[i] = d[i - 1];
+            for j in 0..i {
+                d[j] = v[[i - 1, j]];
+                v[[i, j]] = 0.0;
+                v[[j, i]] = 0.0;
+            }
+        } else {
+            // generate Householder vector
+            for k in 0..i {
+                d[k] /= scale;
+                h += d[k] * d[k];
+            }
+            let mut f = d[i - 1];
+            let mut g = if f > 0.0 { -h.sqrt() } else { h.sqrt() };
+            e[i] = scale * g;
+            h -= f * g;
+            d[i - 1] = f - g;
+            for j in 0..i {
+                e[j] = 0.0;
+            }
+
+            // apply similarity transformation to remaining columns
+            for j in 0..i {
+                f = d[j];
+                v[[j, i]] = f;
+                g = e[j] + v[[j, j]] * f;
+                for k in j + 1..i {
+                    g += v[[k, j]] * d[k];
+                    e[k] += v[[k, j]] * f;
+                }
+                e[j] = g;
+            }
+            f = 0.0;
+            for j in 0..i {
+                e[j] /= h;
+                f += e[j] * d[j];
+            }
+            let hh = f / (h + h);
+            for j in 0..i {
+                e[j] -= hh * d[j];
+            }
+            for j in 0..i {
+                f = d[j];
+                g = e[j];
+                for k in j..i {
+                    v[[k, j]] -= f * e[k] + g * d[k];
+                }
+                d[j] = v[[i - 1, j]];
+                v[[i, j]] = 0.0;
+            }
+        }
+        d[i] = h;
+    }
+
+    // accumulate transformations
+    for i in 0..n - 1 {
+        v[[n - 1, i]] = v[[i, i]];
+        v[[i, i]] = 1.0;
+        let h = d[i + 1];
+        if h != 0.0 {
+            for k in 0..=i {
+                d[k] = v[[k, i + 1]] / h;
+            }
+            for j in 0..=i {
+                let g: f64 = (0..=i).map(|k| v[[k, i + 1]] * v[[k, j]]).sum();
+                for k in 0..=i {
+                    v[[k, j]] -= g * d[k];
+                }
+            }
+        }
+        for k in 0..=i {
+            v[[k, i + 1]] = 0.0;
+        }
+    }
+    for j in 0..n {
+        d[j] = v[[n - 1, j]];
+        v[[n - 1, j]] = 0.0;
+    }
+    v[[n - 1, n - 1]] = 1.0;
+    e[0] = 0.0;
+}
+
+/// Symmetric tridiagonal QL algorithm, `d` receives the eigenvalues and `v`
+/// the eigenvectors
+fn tql2(v: &mut Array2<f64>, d: &mut Array1<f64>, e: &mut Array1<f64>) {
+    let n = v.nrows();
+    for i in 1..n {
+        e[i - 1] = e[i];
+    }
+    e[n - 1] = 0.0;
+
+    let mut f = 0.0;
+    let mut tst1: f64 = 0.0;
+    let eps = f64::EPSILON;
+    for l in 0..n {
+        // find small subdiagonal element
+        tst1 = tst1.max(d[l].abs() + e[l].abs());
+        let mut m = l;
+        while m < n - 1 && e[m].abs() > eps * tst1 {
+            m += 1;
+        }
+
+        // if m == l, d[l] is already an eigenvalue, otherwise iterate
+        if m > l {
+            loop {
+                // compute implicit shift
+                let mut g = d[l];
+                let mut p = (d[l + 1] - g) / (2.0 * e[l]);
+                let mut r = p.hypot(1.0);
+                if p < 0.0 {
+                    r = -r;
+                }
+                d[l] = e[l] / (p + r);
+                d[l + 1] = e[l] * (p + r);
+                let dl1 = d[l + 1];
+                let mut h = g - d[l];
+                for i in l + 2..n {
+                    d[i] -= h;
+                }
+                f += h;
+
+                // implicit QL transformation
+                p = d[m];
+                let (mut c, mut c2, mut c3) = (1.0, 1.0, 1.0);
+                let el1 = e[l + 1];
+                let (mut s, mut s2) = (0.0, 0.0);
+                for i in (l..m).rev() {
+                    c3 = c2;
+                    c2 = c;
+                    s2 = s;
+                    g = c * e[i];
+                    h = c * p;
+                    r = p.hypot(e[i]);
+                    e[i + 1] = s * r;
+                    s = e[i] / r;
+                    c = p / r;
+                    p = c * d[i] - s * g;
+                    d[i + 1] = h + s * (c * g + s * d[i]);
+
+                    // accumulate transformation
+                    for k in 0..n {
+                        h = v[[k, i + 1]];
+                        v[[k, i + 1]] = s * v[[k, i]] + c * h;
+                        v[[k, i]] = c * v[[k, i]] - s * h;
+                    }
+                }
+                p = -s * s2 * c3 * el1 * e[l] / dl1;
+                e[l] = s * p;
+                d[l] = c * p;
+
+                // check for convergence
+                if e[l].abs() <= eps * tst1 {
+                    break;
+                }
+            }
+        }
+        d[l] += f;
+        e[l] = 0.0;
+    }
+}
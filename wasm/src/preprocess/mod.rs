@@ -0,0 +1,162 @@
+//! Turn raw images into observations distributed like MNIST
+//!
+//! Shared by the web canvas, image folders and native tools: invert so the
+//! ink is bright, then fit the ink in 20x20 and centre it by its centre of
+//! mass in 28x28, see `centre_image`.
+mod augment;
+mod centre;
+mod eigen;
+mod normalize;
+mod pca;
+
+pub use augment::{Augmentation, Augmenter};
+pub use centre::{
+    centre_image, centre_observation, centre_of_mass, deskew_dataset, deskew_image,
+    deskew_observation,
+};
+pub use normalize::{Normalization, NormalizeError, Normalizer};
+pub use pca::{Pca, PcaError};
+
+use ndarray::{Array1, Array2, ArrayBase, Data, ErrorKind, Ix2, ShapeError};
+use wasm_bindgen::prelude::*;
+
+/// Width and height of an observation
+pub const IMAGE_SIZE: usize = 28;
+
+/// Value of the background in images with dark ink on a white background
+const WHITE: f64 = 255.0;
+
+/// Convert canvas pixels (RGBA, row-major, dark ink on a white background)
+/// into an observation of length 28 * 28 with values in 0..=255
+#[wasm_bindgen]
+pub fn rgba_to_observation(rgba: &[u8], width: usize, height: usize) -> Result<Vec<f64>, JsError> {
+    Ok(observation_from_rgba(rgba, width, height)?.into_raw_vec())
+}
+
+/// Same as `rgba_to_observation`, for native tools
+pub fn observation_from_rgba(
+    rgba: &[u8],
+    width: usize,
+    height: usize,
+) -> Result<Array1<f64>, ShapeError> {
+    let gray = rgba_to_grayscale(rgba, width, height)?;
+    Ok(grayscale_to_observation(&gray))
+}
+
+/// Shape: (height, width), transparent pixels are composited on white
+pub fn rgba_to_grayscale(
+    rgba: &[u8],
+    width: usize,
+    height: usize,
+) -> Result<Array2<f64>, ShapeError> {
+    let n_bytes = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(4))
+        .ok_or_else(|| ShapeError::from_kind(ErrorKind::Overflow))?;
+    if rgba.len() != n_bytes {
+        return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape));
+    }
+    let pixels = rgba
+        .chunks_exact(4)
+        .map(|p| over_white(luma(p), p[3] as f64));
+    Array2::from_shape_vec((height, width), pixels.collect())
+}
+
+/// Use RGB grayscale coefficients (https://imagej.nih.gov/ij/docs/menus/image.html)
+pub(crate) fn luma(rgb: &[u8]) -> f64 {
+    0.299 * rgb[0] as f64 + 0.587 * rgb[1] as f64 + 0.114 * rgb[2] as f64
+}
+
+/// Composite a gray value with alpha in 0..=255 on a white background
+pub(crate) fn over_white(gray: f64, alpha: f64) -> f64 {
+    let alpha = alpha / 255.0;
+    alpha * gray + (1.0 - alpha) * WHITE
+}
+
+/// Convert a grayscale image (dark ink on a white background) into an
+/// observation of length 28 * 28 with values in 0..=255
+///
+/// The ink is resampled once, straight from the image to its 20x20 box.
+pub fn grayscale_to_observation(gray: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Array1<f64> {
+    let inverted = gray.mapv(|v| (WHITE - v).clamp(0.0, 255.0));
+    centre_image(&inverted)
+        .into_shape(IMAGE_SIZE * IMAGE_SIZE)
+        .unwrap()
+}
+
+/// Bounding box (y, x, height, width) of every pixel that isn't white
+pub fn find_cropped_image(
+    gray: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+) -> Option<(usize, usize, usize, usize)> {
+    bounding_box(gray, |v| v < WHITE)
+}
+
+/// Bounding box (y, x, height, width) of every pixel that is ink
+fn bounding_box(
+    image: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    is_ink: impl Fn(f64) -> bool,
+) -> Option<(usize, usize, usize, usize)> {
+    let mut bounds: Option<(usize, usize, usize, usize)> = None;
+
+    for ((y, x), &v) in image.indexed_iter() {
+        if is_ink(v) {
+            let (min_y, min_x, max_y, max_x) = bounds.get_or_insert((y, x, y, x));
+            *min_y = (*min_y).min(y);
+            *min_x = (*min_x).min(x);
+            *max_y = (*max_y).max(y);
+            *max_x = (*max_x).max(x);
+        }
+    }
+
+    bounds.map(|(min_y, min_x, max_y, max_x)| (min_y, min_x, 1 + max_y - min_y, 1 + max_x - min_x))
+}
+
+/// Antialiased resize, every output pixel is the area weighted average of the
+/// input pixels it covers
+pub fn resize(
+    image: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    height: usize,
+    width: usize,
+) -> Array2<f64> {
+    let rows = area_weights(image.nrows(), height);
+    let cols = area_weights(image.ncols(), width);
+
+    // resize horizontally then vertically
+    let mut horizontal = Array2::zeros((image.nrows(), width));
+    for (mut out, row) in horizontal.rows_mut().into_iter().zip(image.rows()) {
+        for (o, weights) in out.iter_mut().zip(&cols) {
+            *o = weights.iter().map(|&(i, w)| w * row[i]).sum();
+        }
+    }
+
+    let mut resized = Array2::zeros((height, width));
+    for (mut out, weights) in resized.rows_mut().into_iter().zip(&rows) {
+        for &(i, w) in weights {
+            out.scaled_add(w, &horizontal.row(i));
+        }
+    }
+    resized
+}
+
+/// For each output pixel, the input pixels it covers and their normalized weights
+fn area_weights(src: usize, dst: usize) -> Vec<Vec<(usize, f64)>> {
+    let scale = src as f64 / dst as f64;
+
+    (0..dst)
+        .map(|i| {
+            let start = i as f64 * scale;
+            let end = (i + 1) as f64 * scale;
+            let mut weights: Vec<(usize, f64)> = (start.floor() as usize..end.ceil() as usize)
+                .map(|j| {
+                    let overlap = (end.min(j as f64 + 1.0) - start.max(j as f64)).max(0.0);
+                    (j.min(src - 1), overlap)
+                })
+                .filter(|&(_, w)| w > 0.0)
+                .collect();
+
+            let total: f64 = weights.iter().map(|&(_, w)| w).sum();
+            weights.iter_mut().for_each(|(_, w)| *w /= total);
+            weights
+        })
+        .collect()
+}
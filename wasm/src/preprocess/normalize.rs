@@ -0,0 +1,220 @@
+//! Rescale observations before they reach a model
+//!
+//! A `Normalizer` is fitted on the training dataset and then applied unchanged
+//! to the testing dataset and to every observation passed to `predict`.
+use crate::dataset::Dataset;
+use crate::models::CHUNK_SIZE;
+use ndarray::{s, Array1, Array2, ArrayBase, Axis, Data, Ix2, Zip};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Normalization {
+    /// Scale each feature to 0..=1 using the range seen when fitting
+    MinMax,
+    /// Scale every feature by the single range of all the values seen when
+    /// fitting, e.g. all the pixels of an image
+    MinMaxGlobal,
+    /// Scale each feature to mean 0 and standard deviation 1
+    ZScore,
+    /// Scale each observation to unit length
+    L2,
+    /// 1 if the value is above the threshold, 0 otherwise
+    Binarize,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Normalizer {
+    normalization: Normalization,
+    threshold: f64,
+    /// x' = (x - offset) * scale, empty until fitted
+    offset: Array1<f64>,
+    scale: Array1<f64>,
+}
+
+#[wasm_bindgen]
+impl Normalizer {
+    /// `threshold` is only used by `Normalization::Binarize`
+    #[wasm_bindgen(constructor)]
+    pub fn new(normalization: Normalization, threshold: f64) -> Self {
+        Self {
+            normalization,
+            threshold,
+            offset: Array1::zeros(0),
+            scale: Array1::zeros(0),
+        }
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    /// Learn the per feature statistics, only MinMax, MinMaxGlobal and ZScore
+    /// need fitting
+    ///
+    /// A feature whose min equals its max (or whose standard deviation is 0)
+    /// gets a scale of 1.0 and stays unscaled, e.g. a border pixel that is
+    /// always 0 in the training dataset keeps its raw values when predicting.
+    pub fn fit(&mut self, dataset: &Dataset) {
+        let (offset, scale) = match self.normalization {
+            Normalization::MinMax => min_max(dataset),
+            Normalization::MinMaxGlobal => min_max_global(dataset),
+            Normalization::ZScore => mean_std(dataset),
+            Normalization::L2 | Normalization::Binarize => return,
+        };
+        self.offset = offset;
+        self.scale = scale.mapv(|v| if v > 0.0 { 1.0 / v } else { 1.0 });
+    }
+
+    #[wasm_bindgen(js_name = transform)]
+    pub fn js_transform(&self, dataset: &Dataset) -> Result<Dataset, JsError> {
+        Ok(self.transform(dataset)?)
+    }
+
+    /// Normalize a single observation, e.g. the input of `predict`
+    pub fn transform_observation(&self, observation: Vec<f64>) -> Result<Vec<f64>, JsError> {
+        let observation = Array2::from_shape_vec((1, observation.len()), observation)?;
+        Ok(self.transform_observations(&observation)?.into_raw_vec())
+    }
+}
+
+impl_json!(Normalizer);
+
+impl Normalizer {
+    pub fn fit_transform(&mut self, dataset: &Dataset) -> Result<Dataset, NormalizeError> {
+        self.fit(dataset);
+        self.transform(dataset)
+    }
+
+    /// Copy of the dataset with normalized observations
+    pub fn transform(&self, dataset: &Dataset) -> Result<Dataset, NormalizeError> {
+        let mut observations = Array2::zeros((dataset.n_observations(), dataset.data_size()));
+        for (range, chunk) in dataset.observation_chunks(CHUNK_SIZE) {
+            observations
+                .slice_mut(s![range, ..])
+                .assign(&self.transform_observations(&chunk)?);
+        }
+
+        Ok(match self.normalization {
+            // only zeros and ones, keep them compact
+            Normalization::Binarize => {
+                dataset.with_transformed_observations(observations.mapv(|v| v as u8))
+            }
+            _ => dataset.with_transformed_observations(observations),
+        })
+    }
+
+    /// Shape: (n_observations, data_size)
+    pub fn transform_observations(
+        &self,
+        observations: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    ) -> Result<Array2<f64>, NormalizeError> {
+        Ok(match self.normalization {
+            Normalization::MinMax | Normalization::MinMaxGlobal | Normalization::ZScore => {
+                if self.offset.is_empty() {
+                    return Err(NormalizeError::NotFitted);
+                } else if self.offset.len() != observations.ncols() {
+                    return Err(NormalizeError::DataSize {
+                        expected: self.offset.len(),
+                        actual: observations.ncols(),
+                    });
+                }
+                (observations - &self.offset) * &self.scale
+            }
+            Normalization::L2 => {
+                let mut observations = observations.to_owned();
+                for mut row in observations.rows_mut() {
+                    let norm = row.dot(&row).sqrt();
+                    if norm > 0.0 {
+                        row /= norm;
+                    }
+                }
+                observations
+            }
+            Normalization::Binarize => {
+                let threshold = self.threshold;
+                observations.mapv(|v| if v > threshold { 1.0 } else { 0.0 })
+            }
+        })
+    }
+}
+
+/// Per feature (min, max - min)
+fn min_max(dataset: &Dataset) -> (Array1<f64>, Array1<f64>) {
+    let mut min = Array1::from_elem(dataset.data_size(), f64::INFINITY);
+    let mut max = Array1::from_elem(dataset.data_size(), f64::NEG_INFINITY);
+    for (_, chunk) in dataset.observation_chunks(CHUNK_SIZE) {
+        for row in chunk.outer_iter() {
+            Zip::from(&mut min)
+                .and(&mut max)
+                .and(&row)
+                .for_each(|min, max, &v| {
+                    *min = min.min(v);
+                    *max = max.max(v);
+                });
+        }
+    }
+
+    if dataset.n_observations() == 0 {
+        min.fill(0.0);
+        max.fill(0.0);
+    }
+    let range = &max - &min;
+    (min, range)
+}
+
+/// (min, max - min) of all the values, repeated for every feature
+fn min_max_global(dataset: &Dataset) -> (Array1<f64>, Array1<f64>) {
+    let (min, range) = min_max(dataset);
+    let max = (&min + &range).fold(f64::NEG_INFINITY, |max, &v| max.max(v));
+    let min = min.fold(f64::INFINITY, |min, &v| min.min(v));
+    (
+        Array1::from_elem(dataset.data_size(), min),
+        Array1::from_elem(dataset.data_size(), max - min),
+    )
+}
+
+/// Per feature (mean, standard deviation)
+fn mean_std(dataset: &Dataset) -> (Array1<f64>, Array1<f64>) {
+    let mut sum = Array1::zeros(dataset.data_size());
+    let mut sum_squares = Array1::zeros(dataset.data_size());
+    for (_, chunk) in dataset.observation_chunks(CHUNK_SIZE) {
+        sum += &chunk.sum_axis(Axis(0));
+        sum_squares += &chunk.mapv(|v| v * v).sum_axis(Axis(0));
+    }
+
+    let n = dataset.n_observations().max(1) as f64;
+    let mean = sum / n;
+    let variance = sum_squares / n - &mean * &mean;
+    (mean, variance.mapv(|v| v.max(0.0).sqrt()))
+}
+
+#[derive(Debug)]
+pub enum NormalizeError {
+    /// MinMax, MinMaxGlobal and ZScore need `fit` before `transform`
+    NotFitted,
+    /// Observations differ in size from the ones the normalizer was fitted on
+    DataSize { expected: usize, actual: usize },
+}
+impl Error for NormalizeError {}
+
+impl Display for NormalizeError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            NormalizeError::NotFitted => write!(f, "Normalizer must be fitted before transform"),
+            NormalizeError::DataSize { expected, actual } => {
+                write!(
+                    f,
+                    "Normalizer was fitted on observations of size {} but got {}",
+                    expected, actual
+                )
+            }
+        }
+    }
+}
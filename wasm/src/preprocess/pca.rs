@@ -0,0 +1,252 @@
+//! Principal component analysis
+//!
+//! Projects observations onto the directions of largest variance so distance
+//! based models (kNN, k-means) can work in 30-50 dimensions instead of 784.
+//! For MNIST the components reshaped to 28x28 are the "eigen-digits".
+use super::eigen::symmetric_eigen;
+use crate::dataset::Dataset;
+use crate::models::CHUNK_SIZE;
+use ndarray::{s, Array1, Array2, ArrayBase, Axis, Data, Ix2};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+use wasm_bindgen::prelude::*;
+
+/// How many components to keep when fitting
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Selection {
+    Count(usize),
+    /// Fewest components explaining at least this ratio of the variance
+    ExplainedVariance(f64),
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pca {
+    selection: Selection,
+    /// Shape: (data_size)
+    mean: Array1<f64>,
+    /// Shape: (n_components, data_size), orthonormal rows sorted by variance
+    components: Array2<f64>,
+    /// Shape: (n_components), variance along each component
+    explained_variance: Array1<f64>,
+    /// Sum of the variance of every feature
+    total_variance: f64,
+}
+
+#[wasm_bindgen]
+impl Pca {
+    /// Keep `n_components` components
+    #[wasm_bindgen(constructor)]
+    pub fn new(n_components: usize) -> Self {
+        Self::with_selection(Selection::Count(n_components))
+    }
+
+    /// Keep the fewest components explaining at least `ratio` (0..=1) of the variance
+    pub fn with_explained_variance(ratio: f64) -> Self {
+        Self::with_selection(Selection::ExplainedVariance(ratio))
+    }
+
+    /// Compute the mean and principal components of the observations
+    pub fn fit(&mut self, dataset: &Dataset) {
+        let n = dataset.n_observations().max(1) as f64;
+
+        let mut mean = Array1::zeros(dataset.data_size());
+        for (_, chunk) in dataset.observation_chunks(CHUNK_SIZE) {
+            mean += &chunk.sum_axis(Axis(0));
+        }
+        mean /= n;
+
+        // Shape: (data_size, data_size)
+        let mut covariance = Array2::zeros((dataset.data_size(), dataset.data_size()));
+        for (_, chunk) in dataset.observation_chunks(CHUNK_SIZE) {
+            let centred = &chunk - &mean;
+            covariance += &centred.t().dot(&centred);
+        }
+        covariance /= n;
+
+        let (variance, vectors) = symmetric_eigen(covariance);
+        let variance = variance.mapv(|v| v.max(0.0));
+        let total_variance = variance.sum();
+
+        let n_components = match self.selection {
+            Selection::Count(count) => count.min(variance.len()),
+            Selection::ExplainedVariance(ratio) => {
+                let mut explained = 0.0;
+                variance
+                    .iter()
+                    .position(|v| {
+                        explained += v;
+                        explained >= ratio * total_variance
+                    })
+                    .map_or(variance.len(), |i| i + 1)
+            }
+        };
+
+        let mut components = vectors.slice(s![.., ..n_components]).t().to_owned();
+        // the sign of an eigenvector is arbitrary, make the largest element positive
+        for mut component in components.rows_mut() {
+            let largest = component
+                .iter()
+                .copied()
+                .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+                .unwrap_or(0.0);
+            if largest < 0.0 {
+                component *= -1.0;
+            }
+        }
+
+        self.mean = mean;
+        self.components = components;
+        self.explained_variance = variance.slice(s![..n_components]).to_owned();
+        self.total_variance = total_variance;
+    }
+
+    #[wasm_bindgen(js_name = transform)]
+    pub fn js_transform(&self, dataset: &Dataset) -> Result<Dataset, JsError> {
+        Ok(self.transform(dataset)?)
+    }
+
+    #[wasm_bindgen(js_name = inverse_transform)]
+    pub fn js_inverse_transform(&self, dataset: &Dataset) -> Result<Dataset, JsError> {
+        Ok(self.inverse_transform(dataset)?)
+    }
+
+    pub fn transform_observation(&self, observation: Vec<f64>) -> Result<Vec<f64>, JsError> {
+        let observation = Array2::from_shape_vec((1, observation.len()), observation)?;
+        Ok(self.transform_observations(&observation)?.into_raw_vec())
+    }
+
+    /// Reconstruct an observation from its components, e.g. to display it
+    pub fn inverse_transform_observation(
+        &self,
+        transformed: Vec<f64>,
+    ) -> Result<Vec<f64>, JsError> {
+        let transformed = Array2::from_shape_vec((1, transformed.len()), transformed)?;
+        Ok(self
+            .inverse_transform_observations(&transformed)?
+            .into_raw_vec())
+    }
+
+    pub fn n_components(&self) -> usize {
+        self.components.nrows()
+    }
+
+    /// Component `i` with the size of an observation, `None` past the last
+    /// component
+    pub fn component(&self, i: usize) -> Option<Vec<f64>> {
+        (i < self.n_components()).then(|| self.components.row(i).to_vec())
+    }
+
+    /// Ratio of the total variance explained by each component
+    pub fn explained_variance_ratio(&self) -> Vec<f64> {
+        if self.total_variance > 0.0 {
+            (&self.explained_variance / self.total_variance).into_raw_vec()
+        } else {
+            vec![0.0; self.n_components()]
+        }
+    }
+}
+
+impl_json!(Pca);
+
+impl Pca {
+    fn with_selection(selection: Selection) -> Self {
+        Self {
+            selection,
+            mean: Array1::zeros(0),
+            components: Array2::zeros((0, 0)),
+            explained_variance: Array1::zeros(0),
+            total_variance: 0.0,
+        }
+    }
+
+    pub fn fit_transform(&mut self, dataset: &Dataset) -> Result<Dataset, PcaError> {
+        self.fit(dataset);
+        self.transform(dataset)
+    }
+
+    /// Copy of the dataset with observations of size n_components
+    pub fn transform(&self, dataset: &Dataset) -> Result<Dataset, PcaError> {
+        let mut observations = Array2::zeros((dataset.n_observations(), self.n_components()));
+        for (range, chunk) in dataset.observation_chunks(CHUNK_SIZE) {
+            observations
+                .slice_mut(s![range, ..])
+                .assign(&self.transform_observations(&chunk)?);
+        }
+        Ok(dataset.with_transformed_observations(observations))
+    }
+
+    /// Copy of a transformed dataset mapped back to the original features
+    pub fn inverse_transform(&self, dataset: &Dataset) -> Result<Dataset, PcaError> {
+        let mut observations = Array2::zeros((dataset.n_observations(), self.mean.len()));
+        for (range, chunk) in dataset.observation_chunks(CHUNK_SIZE) {
+            observations
+                .slice_mut(s![range, ..])
+                .assign(&self.inverse_transform_observations(&chunk)?);
+        }
+        Ok(dataset.with_transformed_observations(observations))
+    }
+
+    /// Shape: (n_components, data_size)
+    pub fn components(&self) -> &Array2<f64> {
+        &self.components
+    }
+
+    /// (n_observations, data_size) -> (n_observations, n_components)
+    pub fn transform_observations(
+        &self,
+        observations: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    ) -> Result<Array2<f64>, PcaError> {
+        self.check_size(self.mean.len(), observations.ncols())?;
+        Ok((observations - &self.mean).dot(&self.components.t()))
+    }
+
+    /// (n_observations, n_components) -> (n_observations, data_size)
+    pub fn inverse_transform_observations(
+        &self,
+        transformed: &ArrayBase<impl Data<Elem = f64>, Ix2>,
+    ) -> Result<Array2<f64>, PcaError> {
+        self.check_size(self.n_components(), transformed.ncols())?;
+        Ok(transformed.dot(&self.components) + &self.mean)
+    }
+
+    fn check_size(&self, expected: usize, actual: usize) -> Result<(), PcaError> {
+        if self.mean.is_empty() {
+            Err(PcaError::NotFitted)
+        } else if expected != actual {
+            Err(PcaError::DataSize { expected, actual })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PcaError {
+    NotFitted,
+    /// Observations differ in size from the ones fitted on, or from
+    /// n_components for the inverse transform
+    DataSize {
+        expected: usize,
+        actual: usize,
+    },
+}
+impl Error for PcaError {}
+
+impl Display for PcaError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            PcaError::NotFitted => write!(f, "Pca must be fitted before transform"),
+            PcaError::DataSize { expected, actual } => {
+                write!(
+                    f,
+                    "Expected observations of size {} but got {}",
+                    expected, actual
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Estimate how well a model generalizes with stratified k-fold cross-validation
+use crate::dataset::Dataset;
+use crate::models::Model;
+use ndarray_rand::rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+/// Error rate of each fold
+#[derive(Debug, Clone)]
+pub struct CrossValidation {
+    pub fold_errors: Vec<f64>,
+}
+
+impl CrossValidation {
+    pub fn mean(&self) -> f64 {
+        self.fold_errors.iter().sum::<f64>() / self.fold_errors.len() as f64
+    }
+
+    /// Sample standard deviation (n - 1)
+    pub fn std_dev(&self) -> f64 {
+        let n = self.fold_errors.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let variance = self
+            .fold_errors
+            .iter()
+            .map(|e| (e - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1) as f64;
+        variance.sqrt()
+    }
+}
+
+/// Split the indices of `dataset` into `k` folds, each label is spread evenly
+/// across the folds
+///
+/// Every fold holds at least one observation, so `k` must be between 2 and
+/// the number of observations.
+pub fn stratified_folds(
+    dataset: &Dataset,
+    k: usize,
+    seed: u64,
+) -> Result<Vec<Vec<usize>>, ValidationError> {
+    if k < 2 || k > dataset.n_observations() {
+        return Err(ValidationError::Folds {
+            k,
+            n_observations: dataset.n_observations(),
+        });
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut folds = vec![Vec::new(); k];
+    // continue where the previous label stopped so fold sizes stay balanced
+    let mut fold = 0;
+    for mut indices in dataset.indices_by_label() {
+        indices.shuffle(&mut rng);
+        for i in indices {
+            folds[fold].push(i);
+            fold = (fold + 1) % k;
+        }
+    }
+
+    folds.iter_mut().for_each(|f| f.shuffle(&mut rng));
+    Ok(folds)
+}
+
+/// Train a fresh model on k - 1 folds for `n_steps` steps and evaluate it on
+/// the remaining fold, for each of the `k` folds
+pub fn cross_validate<M, F>(
+    dataset: &Dataset,
+    k: usize,
+    seed: u64,
+    n_steps: usize,
+    mut new_model: F,
+) -> Result<CrossValidation, ValidationError>
+where
+    M: Model,
+    F: FnMut() -> M,
+{
+    let folds = stratified_folds(dataset, k, seed)?;
+
+    let fold_errors = (0..k)
+        .map(|i| {
+            let training_indices: Vec<usize> = folds
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .flat_map(|(_, fold)| fold.iter().copied())
+                .collect();
+            let training = dataset.select(&training_indices);
+            let validation = dataset.select(&folds[i]);
+
+            let mut model = new_model();
+            for _ in 0..n_steps {
+                model.step(&training);
+            }
+            model.evaluate(&validation)
+        })
+        .collect();
+
+    Ok(CrossValidation { fold_errors })
+}
+
+#[derive(Debug)]
+pub enum ValidationError {
+    /// Number of folds isn't within 2..=n_observations
+    Folds { k: usize, n_observations: usize },
+}
+impl Error for ValidationError {}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            ValidationError::Folds { k, n_observations } => {
+                write!(
+                    f,
+                    "Cannot split {} observations into {} folds, expected 2 to {} folds",
+                    n_observations, k, n_observations
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,244 @@
+mod common;
+
+use common::clusters;
+use mnist::dataset::Dataset;
+use mnist::models::{AnyModel, ModelError};
+use mnist::preprocess::{Normalization, Normalizer};
+use ndarray::{Array1, Array2};
+
+#[test]
+fn models_are_created_by_name() {
+    let dataset = clusters(20, 100.0);
+    let models = [
+        ("kmeans", r#"{ "n_clusters": 2, "n_features": 2 }"#),
+        ("knn", r#"{ "k": 3 }"#),
+        (
+            "perceptron",
+            r#"{ "learning_rate": 0.01, "n_input": 2, "n_output": 2 }"#,
+        ),
+        (
+            "mlp",
+            r#"{ "learning_rate": 0.1, "batch_size": 4, "hidden_layers": [4], "activation": "tanh", "n_input": 2, "n_output": 2 }"#,
+        ),
+        (
+            "softmax",
+            r#"{ "learning_rate": 0.1, "l2": 0.01, "n_input": 2, "n_output": 2 }"#,
+        ),
+    ];
+
+    for (name, param) in models {
+        let mut model = AnyModel::from_param(name, param).unwrap();
+        model.step(&dataset).unwrap();
+        assert!((0.0..=1.0).contains(&model.evaluate(&dataset).unwrap()));
+        assert_eq!(
+            model.predict_proba_raw(vec![100.0, 100.0]).unwrap().len(),
+            2
+        );
+    }
+}
+
+#[test]
+fn defaults_and_unknown_fields() {
+    // the frontend sends fields only used by the worker, e.g. max_iter and
+    // batch_size
+    let model =
+        AnyModel::from_param("perceptron", r#"{ "learning_rate": 0.1, "max_iter": 5 }"#).unwrap();
+    assert_eq!(
+        model.predict_proba_raw(vec![0.0; 28 * 28]).unwrap().len(),
+        10
+    );
+
+    let model = AnyModel::from_param(
+        "cnn",
+        r#"{ "learning_rate": 0.1, "batch_size": 8, "filters": [2], "hidden_layers": [] }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        model.predict_proba_raw(vec![0.0; 28 * 28]).unwrap().len(),
+        10
+    );
+}
+
+#[test]
+fn invalid_models_are_errors() {
+    assert!(matches!(
+        AnyModel::from_param("svm", "{}"),
+        Err(ModelError::UnknownModel(name)) if name == "svm"
+    ));
+    assert!(matches!(
+        AnyModel::from_param("knn", r#"{ "k": -1 }"#),
+        Err(ModelError::Param(_))
+    ));
+    assert!(matches!(
+        AnyModel::from_param("kmeans", "{}"),
+        Err(ModelError::Param(_))
+    ));
+}
+
+#[test]
+fn invalid_hyperparameters_are_errors() {
+    let invalid = [
+        ("kmeans", r#"{ "n_clusters": 0 }"#, "n_clusters"),
+        ("knn", r#"{ "k": 0 }"#, "k"),
+        (
+            "perceptron",
+            r#"{ "learning_rate": -0.1 }"#,
+            "learning_rate",
+        ),
+        (
+            "mlp",
+            r#"{ "learning_rate": 0.1, "batch_size": 4, "hidden_layers": [4, 0] }"#,
+            "hidden_layers",
+        ),
+        (
+            "mlp",
+            r#"{ "learning_rate": 0.1, "batch_size": 4, "hidden_layers": [] }"#,
+            "hidden_layers",
+        ),
+        (
+            "cnn",
+            r#"{ "learning_rate": 0.1, "batch_size": 4, "filters": [], "hidden_layers": [] }"#,
+            "filters",
+        ),
+        (
+            "cnn",
+            r#"{ "learning_rate": 0.1, "filters": [1, 1, 1, 1, 1], "hidden_layers": [] }"#,
+            "filters",
+        ),
+        ("softmax", r#"{ "learning_rate": 0.1, "l2": -1.0 }"#, "l2"),
+    ];
+    for (name, param, invalid_param) in invalid {
+        assert!(matches!(
+            AnyModel::from_param(name, param),
+            Err(ModelError::InvalidParam { param, .. }) if param == invalid_param
+        ));
+    }
+}
+
+#[test]
+fn knn_with_more_neighbours_than_observations() {
+    let mut model = AnyModel::from_param("knn", r#"{ "k": 50 }"#).unwrap();
+    model.step(&clusters(6, 100.0)).unwrap();
+    let proba = model.predict_proba_raw(vec![0.0, 0.0]).unwrap();
+    assert!((proba.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn json_round_trip_keeps_the_model_and_its_normalizer() {
+    let dataset = clusters(20, 100.0);
+    let mut normalizer = Normalizer::new(Normalization::MinMax, 0.0);
+    let normalized = normalizer.fit_transform(&dataset).unwrap();
+
+    let mut model = AnyModel::from_param(
+        "softmax",
+        r#"{ "learning_rate": 0.5, "n_input": 2, "n_output": 2 }"#,
+    )
+    .unwrap();
+    model.set_normalizer(&normalizer);
+    for _ in 0..10 {
+        model.step(&normalized).unwrap();
+    }
+
+    let mut json = Vec::new();
+    model.write_json(&mut json).unwrap();
+    let loaded = AnyModel::read_json(json.as_slice()).unwrap();
+
+    assert_eq!(loaded.name(), "softmax");
+    assert_eq!(
+        loaded.evaluate(&normalized).unwrap(),
+        model.evaluate(&normalized).unwrap()
+    );
+    // raw observations go through the saved normalizer, JSON may round the
+    // last digit of the weights
+    let expected = model.predict_proba_raw(vec![100.0, 100.0]).unwrap();
+    let actual = loaded.predict_proba_raw(vec![100.0, 100.0]).unwrap();
+    assert!(actual
+        .iter()
+        .zip(&expected)
+        .all(|(a, e)| (a - e).abs() < 1e-12));
+}
+
+#[test]
+fn predict_is_the_most_likely_class_after_normalization() {
+    let dataset = clusters(20, 100.0);
+    let mut normalizer = Normalizer::new(Normalization::MinMax, 0.0);
+    let normalized = normalizer.fit_transform(&dataset).unwrap();
+
+    let mut model = AnyModel::from_param(
+        "softmax",
+        r#"{ "learning_rate": 0.5, "n_input": 2, "n_output": 2 }"#,
+    )
+    .unwrap();
+    model.set_normalizer(&normalizer);
+    for _ in 0..10 {
+        model.step(&normalized).unwrap();
+    }
+
+    for (raw, observation) in dataset
+        .observation_iter()
+        .zip(normalized.observation_iter())
+    {
+        assert_eq!(
+            model.predict_raw(raw.to_vec()).unwrap(),
+            model.model().predict(observation.view())
+        );
+    }
+}
+
+#[test]
+fn observations_of_another_size_are_errors() {
+    let mut model = AnyModel::from_param("perceptron", r#"{ "learning_rate": 0.1 }"#).unwrap();
+    assert!(matches!(
+        model.step(&clusters(4, 100.0)),
+        Err(ModelError::DataSize {
+            expected: 784,
+            actual: 2
+        })
+    ));
+    assert!(model.evaluate(&clusters(4, 100.0)).is_err());
+    assert!(model.predict_proba_raw(vec![0.0; 2]).is_err());
+    assert!(model.predict_raw(vec![0.0; 2]).is_err());
+}
+
+#[test]
+fn datasets_with_another_number_of_classes_are_errors() {
+    // e.g. the 26 classes of EMNIST letters on a model with the default 10
+    let dataset =
+        Dataset::from_labels(Array2::<f64>::zeros((4, 2)), Array1::from_elem(4, 25), 26).unwrap();
+    let params = [
+        ("perceptron", r#"{ "learning_rate": 0.1, "n_input": 2 }"#),
+        ("softmax", r#"{ "learning_rate": 0.1, "n_input": 2 }"#),
+        (
+            "mlp",
+            r#"{ "learning_rate": 0.1, "hidden_layers": [4], "n_input": 2 }"#,
+        ),
+    ];
+    for (name, param) in params {
+        let mut model = AnyModel::from_param(name, param).unwrap();
+        assert!(matches!(
+            model.step(&dataset),
+            Err(ModelError::TargetSize {
+                expected: 10,
+                actual: 26
+            })
+        ));
+        assert!(model.evaluate(&dataset).is_err());
+    }
+
+    let model = AnyModel::from_param(
+        "cnn",
+        r#"{ "learning_rate": 0.1, "filters": [2], "hidden_layers": [] }"#,
+    )
+    .unwrap();
+    let images =
+        Dataset::from_labels(Array2::<f64>::zeros((2, 28 * 28)), Array1::zeros(2), 26).unwrap();
+    assert!(matches!(
+        model.evaluate(&images),
+        Err(ModelError::TargetSize { .. })
+    ));
+
+    // k-means and k-NN take the classes of the dataset
+    let mut model = AnyModel::from_param("knn", r#"{ "k": 1 }"#).unwrap();
+    model.step(&dataset).unwrap();
+    assert_eq!(model.evaluate(&dataset).unwrap(), 0.0);
+}
@@ -0,0 +1,82 @@
+use mnist::dataset::{Dataset, ObservationType};
+use mnist::preprocess::{Augmentation, Augmenter};
+use ndarray::{Array1, Array2};
+
+/// A 6x6 square in the middle of each image, labels 0, 1, 2, ...
+fn dataset(n: usize) -> Dataset {
+    let observations = Array2::from_shape_fn((n, 28 * 28), |(_, i)| {
+        let (y, x) = (i / 28, i % 28);
+        if (11..17).contains(&y) && (11..17).contains(&x) {
+            255u8
+        } else {
+            0
+        }
+    });
+    let labels = Array1::from_shape_fn(n, |i| i % 10);
+    Dataset::from_labels(observations, labels, 10).unwrap()
+}
+
+#[test]
+fn no_distortion_is_identity() {
+    let dataset = dataset(3);
+    let none = Augmentation::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+
+    let augmented = Augmenter::new(none, 0).augment(&dataset).unwrap();
+    assert_eq!(augmented.observation_type(), ObservationType::U8);
+    assert_eq!(augmented.observations(), dataset.observations());
+}
+
+#[test]
+fn seeded_augmentation_is_reproducible() {
+    let dataset = dataset(4);
+    let augmentation = Augmentation::new(2.0, 10.0, 0.1, 0.2, 2.0, 4.0, 5.0);
+
+    let a = Augmenter::new(augmentation, 42).augment(&dataset).unwrap();
+    let b = Augmenter::new(augmentation, 42).augment(&dataset).unwrap();
+    let c = Augmenter::new(augmentation, 7).augment(&dataset).unwrap();
+    assert_eq!(a.observations(), b.observations());
+    assert_ne!(a.observations(), c.observations());
+    assert_ne!(a.observations(), dataset.observations());
+}
+
+#[test]
+fn shift_moves_the_ink() {
+    let dataset = dataset(1);
+    let shift = Augmentation::new(3.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+
+    let observation = Augmenter::new(shift, 1)
+        .augment_observation(&dataset.observation(0))
+        .unwrap();
+    let ink: f64 = observation.sum();
+    assert!((ink - dataset.observation(0).sum()).abs() < 1e-6);
+    assert_ne!(observation, dataset.observation(0));
+}
+
+#[test]
+fn expand_keeps_originals_and_labels() {
+    let dataset = dataset(5);
+
+    let expanded = Augmenter::new(Augmentation::default(), 0)
+        .expand(&dataset, 2)
+        .unwrap();
+    assert_eq!(expanded.n_observations(), 15);
+    assert_eq!(expanded.observation_type(), ObservationType::U8);
+    assert_eq!(
+        expanded.labels().to_vec(),
+        [0, 1, 2, 3, 4, 0, 1, 2, 3, 4, 0, 1, 2, 3, 4]
+    );
+    assert_eq!(expanded.observation(3), dataset.observation(3));
+    assert_ne!(expanded.observation(8), dataset.observation(3));
+}
+
+#[test]
+fn observations_that_are_not_28x28_are_errors() {
+    let dataset = Dataset::from_labels(Array2::<u8>::zeros((3, 4)), Array1::zeros(3), 10).unwrap();
+    let mut augmenter = Augmenter::new(Augmentation::default(), 0);
+
+    assert!(augmenter.augment(&dataset).is_err());
+    assert!(augmenter.expand(&dataset, 1).is_err());
+    assert!(augmenter
+        .augment_observation(&dataset.observation(0))
+        .is_err());
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::{ids, numbered};
+use mnist::dataset::{Batches, DatasetError};
+
+#[test]
+fn batches_cover_dataset_in_order() {
+    let dataset = numbered(10);
+    let batches: Vec<Vec<usize>> = dataset
+        .batches(4, false, false, 0)
+        .map(|b| ids(&b.observations()))
+        .collect();
+
+    assert_eq!(
+        batches,
+        vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]
+    );
+}
+
+#[test]
+fn drop_last_skips_partial_batch() {
+    let dataset = numbered(10);
+    let sizes: Vec<usize> = dataset
+        .batches(4, true, true, 0)
+        .map(|b| b.n_observations())
+        .collect();
+
+    assert_eq!(sizes, vec![4, 4]);
+}
+
+#[test]
+fn shuffled_epochs_are_permutations() {
+    let dataset = numbered(10);
+    let mut batches = Batches::new(10, 5, true, false, 42);
+
+    let mut epochs = Vec::new();
+    for _ in 0..3 {
+        let epoch = [
+            ids(&batches.next_batch(&dataset).unwrap().observations()),
+            ids(&batches.next_batch(&dataset).unwrap().observations()),
+        ]
+        .concat();
+        let mut sorted = epoch.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+        epochs.push(epoch);
+    }
+
+    assert_eq!(batches.epoch(), 2);
+    assert_ne!(epochs[0], epochs[1]);
+}
+
+#[test]
+fn dataset_of_another_size_is_rejected() {
+    let mut batches = Batches::new(10, 4, true, false, 0);
+
+    assert!(matches!(
+        batches.next_batch(&numbered(20)),
+        Err(DatasetError::ObservationCount {
+            expected: 10,
+            actual: 20
+        })
+    ));
+    assert_eq!(
+        batches.next_batch(&numbered(10)).unwrap().n_observations(),
+        4
+    );
+}
+
+#[test]
+fn datasets_without_a_batch_are_errors() {
+    let mut batches = Batches::new(3, 4, false, true, 0);
+    for _ in 0..2 {
+        assert!(matches!(
+            batches.next_batch(&numbered(3)),
+            Err(DatasetError::NoBatch {
+                n_observations: 3,
+                batch_size: 4
+            })
+        ));
+    }
+    assert_eq!(batches.epoch(), 0);
+
+    let mut batches = Batches::new(0, 4, false, false, 0);
+    assert!(matches!(
+        batches.next_batch(&numbered(0)),
+        Err(DatasetError::NoBatch { .. })
+    ));
+    assert_eq!(numbered(3).batches(4, false, true, 0).count(), 0);
+}
+
+#[test]
+fn views_read_the_rows_at_their_indices() {
+    let dataset = numbered(10);
+    let mut batches = Batches::new(10, 3, true, false, 7);
+    let batch = batches.next_batch(&dataset).unwrap();
+
+    let copy = batch.to_dataset();
+    assert_eq!(batch.observations(), copy.observations());
+    assert_eq!(&batch.targets(), copy.targets());
+    assert_eq!(&batch.labels(), copy.labels());
+    assert_eq!(ids(&batch.observations()), batch.indices());
+}
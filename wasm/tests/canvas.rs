@@ -0,0 +1,59 @@
+use mnist::preprocess::{observation_from_rgba, rgba_to_grayscale};
+
+/// Opaque white canvas with a black rectangle drawn at rows y0..y1, columns x0..x1
+fn canvas(width: usize, height: usize, y0: usize, y1: usize, x0: usize, x1: usize) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(4 * width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let v = if (y0..y1).contains(&y) && (x0..x1).contains(&x) {
+                0
+            } else {
+                255
+            };
+            rgba.extend_from_slice(&[v, v, v, 255]);
+        }
+    }
+    rgba
+}
+
+#[test]
+fn drawing_is_cropped_centred_and_inverted() {
+    // the same stroke anywhere on the canvas gives the same observation
+    let a = observation_from_rgba(&canvas(300, 300, 20, 120, 30, 80), 300, 300).unwrap();
+    let b = observation_from_rgba(&canvas(300, 200, 90, 190, 200, 250), 300, 200).unwrap();
+    assert_eq!(a.len(), 28 * 28);
+    assert_eq!(a, b);
+
+    // ink is bright, the padding is black
+    assert_eq!(a[14 * 28 + 14], 255.0);
+    assert_eq!(a[0], 0.0);
+    assert!(a.iter().all(|v| (0.0..=255.0).contains(v)));
+}
+
+#[test]
+fn transparent_pixels_are_white() {
+    let mut rgba = canvas(50, 50, 10, 40, 20, 30);
+    // erase the background to fully transparent black
+    for p in rgba.chunks_exact_mut(4).filter(|p| p[0] == 255) {
+        p.copy_from_slice(&[0, 0, 0, 0]);
+    }
+
+    assert_eq!(
+        observation_from_rgba(&rgba, 50, 50).unwrap(),
+        observation_from_rgba(&canvas(50, 50, 10, 40, 20, 30), 50, 50).unwrap()
+    );
+    assert_eq!(
+        observation_from_rgba(&[0; 4 * 9], 3, 3).unwrap(),
+        ndarray::Array1::<f64>::zeros(28 * 28)
+    );
+}
+
+#[test]
+fn size_must_match_the_pixels() {
+    assert!(rgba_to_grayscale(&[255; 4 * 12], 4, 3).is_ok());
+    assert!(rgba_to_grayscale(&[255; 4 * 12], 4, 4).is_err());
+    assert!(rgba_to_grayscale(&[255; 4 * 12 + 2], 4, 3).is_err());
+    // 4 * width * height wraps around to 0
+    assert!(rgba_to_grayscale(&[], usize::MAX / 4 + 1, 4).is_err());
+    assert!(observation_from_rgba(&[], 4, usize::MAX / 2 + 1).is_err());
+}
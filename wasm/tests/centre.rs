@@ -0,0 +1,82 @@
+use mnist::dataset::{Dataset, ObservationType};
+use mnist::preprocess::{centre_image, centre_of_mass, deskew_dataset, deskew_image};
+use ndarray::{Array1, Array2, Axis};
+
+/// Diagonal stroke leaning right, one pixel per row
+fn slanted() -> Array2<f64> {
+    Array2::from_shape_fn((28, 28), |(y, x)| {
+        if (6..22).contains(&y) && x == 20 - (y - 6) / 2 {
+            255.0
+        } else {
+            0.0
+        }
+    })
+}
+
+#[test]
+fn centre_fits_20x20_by_centre_of_mass() {
+    // 5x10 block in the top left corner
+    let image = Array2::from_shape_fn((28, 28), |(y, x)| if y < 5 && x < 10 { 255.0 } else { 0.0 });
+
+    let centred = centre_image(&image);
+    let rows = centred.rows().into_iter().filter(|r| r.sum() > 0.0).count();
+    let cols = centred
+        .columns()
+        .into_iter()
+        .filter(|c| c.sum() > 0.0)
+        .count();
+    assert_eq!((rows, cols), (10, 20));
+
+    let (cy, cx) = centre_of_mass(&centred).unwrap();
+    assert!((cy - 13.5).abs() <= 0.5 && (cx - 13.5).abs() <= 0.5);
+}
+
+#[test]
+fn empty_image_stays_empty() {
+    assert_eq!(
+        centre_image(&Array2::<f64>::zeros((50, 40))),
+        Array2::<f64>::zeros((28, 28))
+    );
+    assert_eq!(centre_of_mass(&Array2::<f64>::zeros((28, 28))), None);
+}
+
+#[test]
+fn deskew_makes_stroke_upright() {
+    let spread = |image: &Array2<f64>| {
+        let columns: Vec<f64> = image
+            .rows()
+            .into_iter()
+            .filter(|r| r.sum() > 0.0)
+            .map(|r| centre_of_mass(&r.insert_axis(Axis(0))).unwrap().1)
+            .collect();
+        let max = columns.iter().cloned().fold(f64::MIN, f64::max);
+        let min = columns.iter().cloned().fold(f64::MAX, f64::min);
+        max - min
+    };
+
+    let deskewed = deskew_image(&slanted());
+    assert!(spread(&slanted()) > 6.0);
+    assert!(spread(&deskewed) < 1.0);
+}
+
+#[test]
+fn deskew_dataset_keeps_storage_and_labels() {
+    let observations = slanted()
+        .mapv(|v| v as u8)
+        .into_shape((1, 28 * 28))
+        .unwrap();
+    let dataset = Dataset::new(observations, Array2::ones((1, 1)), Array1::zeros(1)).unwrap();
+
+    let deskewed = deskew_dataset(&dataset).unwrap();
+    assert_eq!(deskewed.observation_type(), ObservationType::U8);
+    assert_eq!(deskewed.labels(), dataset.labels());
+    assert_ne!(deskewed.observations(), dataset.observations());
+
+    let wrong_size = Dataset::new(
+        Array2::<u8>::zeros((1, 10)),
+        Array2::ones((1, 1)),
+        Array1::zeros(1),
+    )
+    .unwrap();
+    assert!(deskew_dataset(&wrong_size).is_err());
+}
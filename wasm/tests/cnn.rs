@@ -0,0 +1,154 @@
+use mnist::dataset::Dataset;
+use mnist::models::{Activation, ConvolutionalNeuralNetwork, Model, ModelError};
+use ndarray::{Array1, Array2};
+use serde_json::Value;
+
+/// Normalized 28x28 images of a vertical (label 0) or horizontal (label 1) bar at
+/// different positions
+fn bars(n: usize) -> Dataset {
+    let observations = Array2::from_shape_fn((n, 28 * 28), |(i, j)| {
+        let (y, x) = (j / 28, j % 28);
+        let position = 4 + (i / 2) * 3 % 20;
+        let on_bar = match i % 2 {
+            0 => x / 2 == position / 2,
+            _ => y / 2 == position / 2,
+        };
+        if on_bar {
+            1.0
+        } else {
+            0.0
+        }
+    });
+    let labels = Array1::from_shape_fn(n, |i| i % 2);
+    Dataset::from_labels(observations, labels, 2).unwrap()
+}
+
+#[test]
+fn learns_bar_orientation() {
+    let dataset = bars(16);
+    let mut model =
+        ConvolutionalNeuralNetwork::new(0.1, vec![2, 2], vec![8], 2, Activation::Relu, 0).unwrap();
+    let initial_loss = model.loss(&dataset);
+    for epoch in 0..20 {
+        for batch in dataset.batches(4, true, false, epoch) {
+            model.step_batch(&batch);
+        }
+    }
+
+    assert!(model.loss(&dataset) < initial_loss / 4.0);
+    assert_eq!(model.evaluate(&dataset), 0.0);
+    assert_eq!(model.predict_batch(&dataset), dataset.labels());
+}
+
+#[test]
+fn predictions_are_probabilities() {
+    let model =
+        ConvolutionalNeuralNetwork::new(0.1, vec![3], vec![], 10, Activation::Tanh, 1).unwrap();
+    let proba = model.predict_proba_raw(vec![0.5; 28 * 28]).unwrap();
+
+    assert_eq!(proba.len(), 10);
+    assert!(proba.iter().all(|&p| p > 0.0 && p < 1.0));
+    assert!((proba.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn saved_model_predicts_the_same() {
+    let dataset = bars(4);
+    let mut model =
+        ConvolutionalNeuralNetwork::new(0.1, vec![2], vec![4], 2, Activation::Sigmoid, 2).unwrap();
+    model.step(&dataset);
+
+    let mut buf = Vec::new();
+    model.write_json(&mut buf).unwrap();
+    let restored = ConvolutionalNeuralNetwork::read_json(buf.as_slice()).unwrap();
+    let observation = dataset.observation(1);
+    assert_eq!(
+        restored.predict_proba(observation.view()),
+        model.predict_proba(observation.view())
+    );
+}
+
+#[test]
+fn step_covers_datasets_larger_than_a_batch() {
+    // four blocks shrink the image to 1x1
+    let dataset = bars(100);
+    let mut model =
+        ConvolutionalNeuralNetwork::new(0.1, vec![1, 1, 1, 1], vec![], 2, Activation::Relu, 4)
+            .unwrap();
+    let error_rate = model.step(&dataset);
+
+    assert!((0.0..=1.0).contains(&error_rate));
+    assert_eq!(model.predict_batch(&dataset).len(), 100);
+}
+
+#[test]
+fn observations_that_are_not_28x28_are_errors() {
+    let model =
+        ConvolutionalNeuralNetwork::new(0.1, vec![2], vec![], 2, Activation::Relu, 3).unwrap();
+    assert!(matches!(
+        model.predict_proba_raw(vec![0.0; 27 * 27]),
+        Err(ModelError::DataSize {
+            expected: 784,
+            actual: 729
+        })
+    ));
+}
+
+#[test]
+fn more_than_four_convolution_blocks_are_errors() {
+    assert!(matches!(
+        ConvolutionalNeuralNetwork::new(0.1, vec![1; 5], vec![], 2, Activation::Relu, 0),
+        Err(ModelError::InvalidParam {
+            param: "filters",
+            ..
+        })
+    ));
+}
+
+/// Kernel `i` of convolution `l` in the JSON of a model
+fn kernel(model: &Value, l: usize, i: usize) -> f64 {
+    model["conv"][l]["kernels"]["data"][i].as_f64().unwrap()
+}
+
+fn with_kernel(model: &Value, l: usize, i: usize, value: f64) -> ConvolutionalNeuralNetwork {
+    let mut model = model.clone();
+    model["conv"][l]["kernels"]["data"][i] = value.into();
+    serde_json::from_value(model).unwrap()
+}
+
+#[test]
+fn convolution_gradients_match_finite_differences() {
+    // smooth inputs and tanh avoid the kinks of relu and ties in the pooling
+    let observations = Array2::from_shape_fn((3, 28 * 28), |(i, j)| {
+        ((i * 7 + j) as f64 * 0.37).sin() * 0.5 + 0.5
+    });
+    let dataset = Dataset::from_labels(observations, Array1::from_vec(vec![0, 1, 2]), 3).unwrap();
+    let learning_rate = 1e-3;
+    let mut model =
+        ConvolutionalNeuralNetwork::new(learning_rate, vec![2, 3], vec![4], 3, Activation::Tanh, 5)
+            .unwrap();
+    let before = serde_json::to_value(&model).unwrap();
+
+    // one step over the whole dataset moves every kernel by
+    // -learning_rate times its gradient
+    model.step(&dataset);
+    let after = serde_json::to_value(&model).unwrap();
+
+    let epsilon = 1e-6;
+    for (l, i) in [(0, 0), (0, 13), (0, 49), (1, 0), (1, 77), (1, 149)] {
+        let w = kernel(&before, l, i);
+        let analytic = (w - kernel(&after, l, i)) / learning_rate;
+        let numeric = (with_kernel(&before, l, i, w + epsilon).loss(&dataset)
+            - with_kernel(&before, l, i, w - epsilon).loss(&dataset))
+            / (2.0 * epsilon);
+
+        assert!(
+            (analytic - numeric).abs() <= 1e-6 + 1e-4 * numeric.abs(),
+            "conv {} kernel {}: {} != {}",
+            l,
+            i,
+            analytic,
+            numeric
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//! Datasets shared by the integration tests, each test only uses some of them
+#![allow(dead_code)]
+use mnist::dataset::Dataset;
+use ndarray::{Array1, Array2, ArrayBase, Data, Ix2};
+
+/// Two well separated clusters `distance` apart, label i % 2
+pub fn clusters(n: usize, distance: f64) -> Dataset {
+    let observations = Array2::from_shape_fn((n, 2), |(i, j)| {
+        ((i % 2) as f64 + (i + j) as f64 * 1e-4) * distance
+    });
+    let labels = Array1::from_shape_fn(n, |i| i % 2);
+    Dataset::from_labels(observations, labels, 2).unwrap()
+}
+
+/// Observation i is [i, i] with label i % 10
+pub fn numbered(n: usize) -> Dataset {
+    let observations = Array2::from_shape_fn((n, 2), |(i, _)| i as f64);
+    let labels = Array1::from_shape_fn(n, |i| i % 10);
+    Dataset::from_labels(observations, labels, 10).unwrap()
+}
+
+/// Which observations of `numbered` these are
+pub fn ids(observations: &ArrayBase<impl Data<Elem = f64>, Ix2>) -> Vec<usize> {
+    observations.column(0).iter().map(|&v| v as usize).collect()
+}
@@ -0,0 +1,86 @@
+use mnist::dataset::{CsvError, CsvOptions, Dataset, ObservationType};
+
+const KAGGLE: &str = "label,pixel0,pixel1,pixel2
+1,0,128,255
+7,255,0,3
+";
+
+#[test]
+fn read_kaggle_format() {
+    let dataset = Dataset::read_csv(KAGGLE.as_bytes(), &CsvOptions::default()).unwrap();
+
+    assert_eq!(dataset.n_observations(), 2);
+    assert_eq!(dataset.data_size(), 3);
+    assert_eq!(dataset.labels().to_vec(), vec![1, 7]);
+    assert_eq!(
+        dataset.observations().row(0).to_vec(),
+        vec![0.0, 128.0, 255.0]
+    );
+    assert_eq!(dataset.targets()[(1, 7)], 1.0);
+    assert_eq!(dataset.observation_type(), ObservationType::U8);
+}
+
+#[test]
+fn fractional_or_scaled_pixels_are_stored_as_f32() {
+    let csv = "1,0,128,255\n7,255,0.5,3\n";
+    let dataset = Dataset::read_csv(csv.as_bytes(), &CsvOptions::new(false, 0, 1.0, 10)).unwrap();
+    assert_eq!(dataset.observation_type(), ObservationType::F32);
+    assert_eq!(
+        dataset.observations().to_owned(),
+        ndarray::array![[0.0, 128.0, 255.0], [255.0, 0.5, 3.0]]
+    );
+
+    let options = CsvOptions::new(true, 0, 1.0 / 255.0, 10);
+    let scaled = Dataset::read_csv(KAGGLE.as_bytes(), &options).unwrap();
+    assert_eq!(scaled.observation_type(), ObservationType::F32);
+}
+
+#[test]
+fn write_read_round_trip_with_label_last() {
+    let dataset = Dataset::read_csv(KAGGLE.as_bytes(), &CsvOptions::default()).unwrap();
+    let options = CsvOptions::new(false, 3, 255.0, 10);
+
+    let mut buf = Vec::new();
+    dataset.write_csv(&mut buf, &options).unwrap();
+    assert!(String::from_utf8_lossy(&buf).starts_with("0,0.5019607843137255,1,1\n"));
+
+    let decoded = Dataset::read_csv(buf.as_slice(), &options).unwrap();
+    assert_eq!(decoded.labels(), dataset.labels());
+    assert_eq!(decoded.observations(), dataset.observations());
+}
+
+#[test]
+fn label_out_of_range_is_rejected() {
+    let csv = "10,0,0,0\n";
+    let options = CsvOptions::new(false, 0, 1.0, 10);
+
+    assert!(matches!(
+        Dataset::read_csv(csv.as_bytes(), &options),
+        Err(CsvError::Label {
+            line: 1,
+            label: 10,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn label_column_out_of_range_is_rejected() {
+    let dataset = Dataset::read_csv(KAGGLE.as_bytes(), &CsvOptions::default()).unwrap();
+    let options = CsvOptions::new(false, 4, 1.0, 10);
+
+    assert!(matches!(
+        dataset.write_csv(Vec::new(), &options),
+        Err(CsvError::LabelColumn {
+            label_column: 4,
+            n_columns: 4
+        })
+    ));
+    assert!(matches!(
+        Dataset::read_csv("1,0,0,0\n".as_bytes(), &options),
+        Err(CsvError::LabelColumn {
+            label_column: 4,
+            n_columns: 4
+        })
+    ));
+}
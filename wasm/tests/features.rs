@@ -0,0 +1,86 @@
+use mnist::dataset::Dataset;
+use mnist::features::{extract_features, Feature};
+use ndarray::{Array1, Array2};
+
+/// Vertical bar in columns 10..14
+fn vertical_bar() -> Array2<f64> {
+    Array2::from_shape_fn(
+        (28, 28),
+        |(_, x)| if (10..14).contains(&x) { 255.0 } else { 0.0 },
+    )
+}
+
+#[test]
+fn zoning_and_projection() {
+    let image = vertical_bar();
+
+    let zones = Feature::Zoning.extract(&image).unwrap();
+    assert_eq!(zones.len(), 16);
+    // columns 10..14 are all in the second column of zones (7..14)
+    for (i, &v) in zones.iter().enumerate() {
+        let expected = if i % 4 == 1 { 4.0 / 7.0 } else { 0.0 };
+        assert!((v - expected).abs() < 1e-12);
+    }
+
+    let histogram = Feature::Projection.extract(&image).unwrap();
+    assert_eq!(histogram.len(), 56);
+    assert!(histogram
+        .iter()
+        .take(28)
+        .all(|&v| (v - 4.0 / 28.0).abs() < 1e-12));
+    assert_eq!(histogram[28 + 12], 1.0);
+    assert_eq!(histogram[28], 0.0);
+}
+
+#[test]
+fn hog_of_vertical_edges_is_horizontal_gradient() {
+    let descriptor = Feature::Hog.extract(&vertical_bar()).unwrap();
+    assert_eq!(descriptor.len(), Feature::Hog.size());
+
+    // gradient along x is angle 0, split between the bins centred on 10 and 170 degrees
+    let mut bins = [0.0; 9];
+    for (i, v) in descriptor.iter().enumerate() {
+        bins[i % 9] += v;
+    }
+    assert!(bins[0] > 0.0 && (bins[0] - bins[8]).abs() < 1e-9);
+    assert!(bins[1..8].iter().all(|&v| v == 0.0));
+
+    // every 36 value block is unit length or empty
+    for block in descriptor.to_vec().chunks(36) {
+        let norm = block.iter().map(|v| v * v).sum::<f64>().sqrt();
+        assert!(norm == 0.0 || (norm - 1.0).abs() < 1e-9);
+    }
+    assert_eq!(
+        Feature::Hog
+            .extract(&Array2::<f64>::zeros((28, 28)))
+            .unwrap(),
+        Array1::<f64>::zeros(324)
+    );
+}
+
+#[test]
+fn extracted_dataset_keeps_labels() {
+    let observations = Array2::from_shape_fn((3, 28 * 28), |(i, j)| ((i + j) % 256) as u8);
+    let labels = Array1::from_vec(vec![2, 0, 1]);
+    let dataset = Dataset::from_labels(observations, labels, 3).unwrap();
+
+    for feature in [Feature::Hog, Feature::Zoning, Feature::Projection] {
+        let features = extract_features(&dataset, feature).unwrap();
+        assert_eq!(features.data_size(), feature.size());
+        assert_eq!(features.labels(), dataset.labels());
+        assert_eq!(features.targets(), dataset.targets());
+    }
+}
+
+#[test]
+fn observations_that_are_not_28x28_are_rejected() {
+    let observations = Array2::<u8>::zeros((2, 27 * 27));
+    let labels = Array1::from_vec(vec![0, 1]);
+    let dataset = Dataset::from_labels(observations, labels, 2).unwrap();
+
+    assert!(extract_features(&dataset, Feature::Zoning).is_err());
+    for feature in [Feature::Hog, Feature::Zoning, Feature::Projection] {
+        assert!(feature.extract(&Array2::<f64>::zeros((0, 0))).is_err());
+        assert!(feature.extract(&Array2::<f64>::zeros((32, 32))).is_err());
+    }
+}
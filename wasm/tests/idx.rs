@@ -0,0 +1,245 @@
+use flate2::read::GzDecoder;
+use mnist::dataset::{
+    read_idx, write_gz_idx, write_idx, Dataset, IdxError, IdxType, ObservationType, EMNIST, MNIST,
+};
+use ndarray::{array, s, Array2, Array3, ArrayD, IxDyn};
+use std::io::Read;
+
+const TEST_IMAGES: &[u8] = include_bytes!("../src/dataset/data/t10k-images-idx3-ubyte.gz");
+const TEST_LABELS: &[u8] = include_bytes!("../src/dataset/data/t10k-labels-idx1-ubyte.gz");
+
+fn gunzip(data: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut buf).unwrap();
+    buf
+}
+
+/// IDX file of shape (2, 2) with big-endian `payload`
+fn idx_2x2(data_type: IdxType, payload: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0, 0, data_type.code(), 2, 0, 0, 0, 2, 0, 0, 0, 2];
+    buf.extend_from_slice(payload);
+    buf
+}
+
+#[test]
+fn read_idx_decodes_big_endian_elements() {
+    let expected = array![[1.0, -2.0], [127.0, -128.0]].into_dyn();
+
+    let i8s = idx_2x2(IdxType::I8, &[0x01, 0xFE, 0x7F, 0x80]);
+    let (header, arr) = read_idx::<f64, _>(i8s.as_slice()).unwrap();
+    assert_eq!(header.data_type, IdxType::I8);
+    assert_eq!(header.dims, vec![2, 2]);
+    assert_eq!(arr, expected);
+
+    let i16s = idx_2x2(
+        IdxType::I16,
+        &[0x00, 0x01, 0xFF, 0xFE, 0x00, 0x7F, 0xFF, 0x80],
+    );
+    assert_eq!(read_idx::<f64, _>(i16s.as_slice()).unwrap().1, expected);
+
+    let i32s: Vec<u8> = [1i32, -2, 127, -128]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect();
+    assert_eq!(&i32s[4..8], &[0xFF, 0xFF, 0xFF, 0xFE]);
+    let i32s = idx_2x2(IdxType::I32, &i32s);
+    assert_eq!(read_idx::<f64, _>(i32s.as_slice()).unwrap().1, expected);
+
+    let f32s: Vec<u8> = [1f32, -2.0, 127.0, -128.0]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect();
+    assert_eq!(&f32s[..4], &[0x3F, 0x80, 0x00, 0x00]);
+    let f32s = idx_2x2(IdxType::F32, &f32s);
+    assert_eq!(read_idx::<f64, _>(f32s.as_slice()).unwrap().1, expected);
+
+    let f64s: Vec<u8> = [1f64, -2.0, 127.0, -128.0]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect();
+    assert_eq!(&f64s[..8], &[0x3F, 0xF0, 0, 0, 0, 0, 0, 0]);
+    let f64s = idx_2x2(IdxType::F64, &f64s);
+    assert_eq!(read_idx::<f64, _>(f64s.as_slice()).unwrap().1, expected);
+}
+
+#[test]
+fn write_read_round_trip_for_every_type() {
+    let arr = array![[[0.0, 1.5], [-3.0, 100.0]], [[-100.0, 7.0], [42.0, -0.5]]].into_dyn();
+    let rounded = arr.mapv(f64::round);
+
+    for &data_type in &[IdxType::I8, IdxType::I16, IdxType::I32] {
+        let mut buf = Vec::new();
+        write_idx(&mut buf, data_type, &arr).unwrap();
+        let (header, decoded) = read_idx::<f64, _>(buf.as_slice()).unwrap();
+        assert_eq!(header.data_type, data_type);
+        assert_eq!(header.dims, vec![2, 2, 2]);
+        assert_eq!(decoded, rounded);
+    }
+    for &data_type in &[IdxType::F32, IdxType::F64] {
+        let mut buf = Vec::new();
+        write_idx(&mut buf, data_type, &arr).unwrap();
+        assert_eq!(read_idx::<f64, _>(buf.as_slice()).unwrap().1, arr);
+    }
+
+    let bytes = ArrayD::from_shape_fn(IxDyn(&[16, 16]), |i| (i[0] * 16 + i[1]) as u8);
+    let mut buf = Vec::new();
+    write_idx(&mut buf, IdxType::U8, &bytes).unwrap();
+    assert_eq!(read_idx::<u8, _>(buf.as_slice()).unwrap().1, bytes);
+}
+
+#[test]
+fn overflowing_dimensions_are_rejected() {
+    let mut buf = vec![0, 0, IdxType::F64.code(), 8];
+    for _ in 0..8 {
+        buf.extend_from_slice(&u32::MAX.to_be_bytes());
+    }
+    assert!(matches!(
+        read_idx::<f64, _>(buf.as_slice()),
+        Err(IdxError::Shape(_))
+    ));
+}
+
+#[test]
+fn write_idx_matches_bundled_files() {
+    let dataset = MNIST::read_testing_gz(TEST_IMAGES, TEST_LABELS).unwrap();
+
+    let mut images = Vec::new();
+    let mut labels = Vec::new();
+    MNIST::write_idx(&dataset, &mut images, &mut labels).unwrap();
+
+    assert!(images == gunzip(TEST_IMAGES));
+    assert!(labels == gunzip(TEST_LABELS));
+}
+
+#[test]
+fn write_gz_round_trip() {
+    let dataset = MNIST::read_testing_gz(TEST_IMAGES, TEST_LABELS).unwrap();
+
+    let mut images = Vec::new();
+    let mut labels = Vec::new();
+    MNIST::write_gz(&dataset, &mut images, &mut labels).unwrap();
+    let decoded = MNIST::read_testing_gz(images.as_slice(), labels.as_slice()).unwrap();
+
+    assert_eq!(decoded.observation_type(), ObservationType::U8);
+    assert_eq!(decoded.observations(), dataset.observations());
+    assert_eq!(decoded.targets(), dataset.targets());
+    assert_eq!(decoded.labels(), dataset.labels());
+}
+
+#[test]
+fn write_idx_keeps_floats_and_large_labels() {
+    let observations = array![[0.25, 0.5], [0.75, 1.0]];
+    let dataset = Dataset::from_labels(observations.clone(), array![299, 7], 300).unwrap();
+
+    let mut images = Vec::new();
+    let mut labels = Vec::new();
+    MNIST::write_idx(&dataset, &mut images, &mut labels).unwrap();
+
+    let (header, decoded) = read_idx::<f64, _>(images.as_slice()).unwrap();
+    assert_eq!(header.data_type, IdxType::F64);
+    assert_eq!(decoded, observations.into_dyn());
+    let (header, decoded) = read_idx::<usize, _>(labels.as_slice()).unwrap();
+    assert_eq!(header.data_type, IdxType::I32);
+    assert_eq!(decoded, array![299, 7].into_dyn());
+}
+
+#[test]
+fn f32_images_round_trip_as_f32() {
+    let observations = Array2::from_shape_fn((2, 28 * 28), |(i, j)| (i * j) as f32 / 7.0);
+    let dataset = Dataset::from_labels(observations, array![3, 9], 10).unwrap();
+
+    let mut images = Vec::new();
+    let mut labels = Vec::new();
+    MNIST::write_idx(&dataset, &mut images, &mut labels).unwrap();
+    let decoded = MNIST::read_idx(images.as_slice(), labels.as_slice()).unwrap();
+
+    assert_eq!(decoded.observation_type(), ObservationType::F32);
+    assert_eq!(decoded.stored_observations(), dataset.stored_observations());
+    assert_eq!(decoded.labels(), dataset.labels());
+}
+
+#[test]
+fn truncated_images_are_rejected() {
+    let mut images = Vec::new();
+    let mut labels = Vec::new();
+    let dataset = MNIST::read_testing_gz(TEST_IMAGES, TEST_LABELS).unwrap();
+    MNIST::write_idx(&dataset, &mut images, &mut labels).unwrap();
+
+    let err = read_idx::<u8, _>(&images[..images.len() - 1]).unwrap_err();
+    assert!(matches!(
+        err,
+        IdxError::Truncated { expected, actual } if expected == actual + 1
+    ));
+}
+
+#[test]
+fn count_mismatch_is_rejected() {
+    assert!(matches!(
+        MNIST::read_training_gz(TEST_IMAGES, TEST_LABELS),
+        Err(IdxError::CountMismatch { .. })
+    ));
+}
+
+#[test]
+fn read_gz_derives_n_observations() {
+    let dataset = MNIST::read_testing_gz(TEST_IMAGES, TEST_LABELS).unwrap();
+    let n = 1_000;
+    let subset = Dataset::new(
+        dataset.observations().slice(s![..n, ..]).to_owned(),
+        dataset.targets().slice(s![..n, ..]).to_owned(),
+        dataset.labels().slice(s![..n]).to_owned(),
+    )
+    .unwrap();
+
+    let mut images = Vec::new();
+    let mut labels = Vec::new();
+    MNIST::write_gz(&subset, &mut images, &mut labels).unwrap();
+    let decoded = MNIST::read_gz(images.as_slice(), labels.as_slice()).unwrap();
+
+    assert_eq!(decoded.n_observations(), n);
+    assert_eq!(decoded.observations(), subset.observations());
+    assert_eq!(decoded.labels(), subset.labels());
+}
+
+#[test]
+fn emnist_letters_are_transposed_and_zero_based() {
+    let mut images = Array3::<u8>::zeros((2, 28, 28));
+    images[(0, 0, 1)] = 255;
+    let labels = array![1u8, 26];
+
+    let mut images_gz = Vec::new();
+    let mut labels_gz = Vec::new();
+    write_gz_idx(&mut images_gz, IdxType::U8, &images).unwrap();
+    write_gz_idx(&mut labels_gz, IdxType::U8, &labels).unwrap();
+    let dataset = EMNIST::read_letters_gz(images_gz.as_slice(), labels_gz.as_slice()).unwrap();
+
+    assert_eq!(dataset.observations()[(0, 28)], 255.0);
+    assert_eq!(dataset.labels(), array![0, 25]);
+    assert_eq!(dataset.target_size(), 26);
+    assert_eq!(dataset.class_names()[25], "Z");
+}
+
+#[test]
+fn negative_and_fractional_labels_are_rejected() {
+    let mut images = Vec::new();
+    write_idx(&mut images, IdxType::U8, &Array3::<u8>::zeros((2, 28, 28))).unwrap();
+
+    let mut labels = Vec::new();
+    write_idx(&mut labels, IdxType::I8, &array![1.0, -1.0]).unwrap();
+    assert!(matches!(
+        MNIST::read_idx(images.as_slice(), labels.as_slice()),
+        Err(IdxError::Label { label, .. }) if label == -1.0
+    ));
+
+    let mut labels = Vec::new();
+    write_idx(&mut labels, IdxType::F32, &array![1.5, 2.0]).unwrap();
+    assert!(matches!(
+        MNIST::read_idx(images.as_slice(), labels.as_slice()),
+        Err(IdxError::Label { label, .. }) if label == 1.5
+    ));
+
+    let mut labels = Vec::new();
+    write_idx(&mut labels, IdxType::I16, &array![9.0, 0.0]).unwrap();
+    let dataset = MNIST::read_idx(images.as_slice(), labels.as_slice()).unwrap();
+    assert_eq!(dataset.labels(), array![9, 0]);
+}
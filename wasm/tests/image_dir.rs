@@ -0,0 +1,98 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use mnist::dataset::{Dataset, ImageError};
+use mnist::preprocess::observation_from_rgba;
+use std::{fs, path::PathBuf};
+
+/// 8x8 white image with a black vertical bar
+fn bar() -> Vec<u8> {
+    (0..64)
+        .map(|i| if i % 8 == 3 || i % 8 == 4 { 0 } else { 255 })
+        .collect()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mnist-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_png(path: PathBuf, gray: &[u8]) {
+    let mut encoder = png::Encoder::new(fs::File::create(path).unwrap(), 8, 8);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(gray).unwrap();
+}
+
+#[test]
+fn load_png_and_pgm_folders() {
+    let root = temp_dir("images");
+    fs::create_dir(root.join("1")).unwrap();
+    fs::create_dir(root.join("7")).unwrap();
+    fs::create_dir(root.join("notes")).unwrap();
+    write_png(root.join("1").join("a.png"), &bar());
+    let mut pgm = b"P5\n# bar\n8 8\n255\n".to_vec();
+    pgm.extend(bar());
+    fs::write(root.join("7").join("b.pgm"), pgm).unwrap();
+    fs::write(root.join("7").join("readme.txt"), "skipped").unwrap();
+
+    let dataset = Dataset::from_image_dir(&root, 10).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(dataset.n_observations(), 2);
+    assert_eq!(dataset.data_size(), 28 * 28);
+    assert_eq!(dataset.labels().to_vec(), vec![1, 7]);
+    assert_eq!(dataset.observations().row(0), dataset.observations().row(1));
+
+    // ink is bright and centred, background is black
+    let image = dataset
+        .observations()
+        .row(0)
+        .to_shape((28, 28))
+        .unwrap()
+        .to_owned();
+    assert_eq!(image[(14, 14)], 255.0);
+    assert_eq!(image[(14, 0)], 0.0);
+    // the 8 pixel tall bar is fitted in the 20x20 box of MNIST
+    let rows_with_ink = image.rows().into_iter().filter(|r| r.sum() > 0.0).count();
+    assert_eq!(rows_with_ink, 20);
+
+    // same preprocessing as the canvas
+    let rgba: Vec<u8> = bar().iter().flat_map(|&v| [v, v, v, 255]).collect();
+    assert_eq!(
+        observation_from_rgba(&rgba, 8, 8).unwrap(),
+        dataset.observations().row(0)
+    );
+}
+
+#[test]
+fn label_out_of_range_is_rejected() {
+    let root = temp_dir("labels");
+    fs::create_dir(root.join("12")).unwrap();
+
+    let result = Dataset::from_image_dir(&root, 10);
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(matches!(
+        result,
+        Err(ImageError::Label {
+            label: 12,
+            n_classes: 10
+        })
+    ));
+}
+
+#[test]
+fn oversized_pgm_is_rejected() {
+    let root = temp_dir("oversized");
+    fs::create_dir(root.join("3")).unwrap();
+    let pgm = format!("P2\n{} 2\n255\n0 0\n", usize::MAX);
+    fs::write(root.join("3").join("a.pgm"), pgm).unwrap();
+
+    let result = Dataset::from_image_dir(&root, 10);
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(matches!(result, Err(ImageError::Pgm(_))));
+}
@@ -0,0 +1,138 @@
+use mnist::dataset::{Dataset, DatasetError, ObservationType};
+use ndarray::{array, Array1, Array2};
+
+#[test]
+fn from_raw_builds_one_hot_targets() {
+    let dataset = Dataset::from_raw(vec![0u8, 1, 2, 3, 4, 5], 2, vec![2, 0, 1], 3).unwrap();
+
+    assert_eq!(dataset.observation_type(), ObservationType::U8);
+    assert_eq!(
+        dataset.observations().to_owned(),
+        array![[0.0, 1.0], [2.0, 3.0], [4.0, 5.0]]
+    );
+    assert_eq!(
+        dataset.targets(),
+        array![[0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]
+    );
+    assert_eq!(dataset.labels(), array![2, 0, 1]);
+}
+
+#[test]
+fn invalid_shapes_are_errors() {
+    assert!(matches!(
+        Dataset::from_raw(vec![0.0; 5], 2, vec![0, 1, 0], 2),
+        Err(DatasetError::Length {
+            expected: 6,
+            actual: 5
+        })
+    ));
+    assert!(matches!(
+        Dataset::from_raw(vec![0.0; 4], 2, vec![0, 3], 2),
+        Err(DatasetError::Label {
+            label: 3,
+            n_classes: 2
+        })
+    ));
+    assert!(matches!(
+        Dataset::new(
+            Array2::<f64>::zeros((3, 2)),
+            Array2::zeros((2, 2)),
+            Array1::zeros(3)
+        ),
+        Err(DatasetError::CountMismatch {
+            expected: 3,
+            actual: 2
+        })
+    ));
+
+    let a = Dataset::from_raw(vec![0.0; 4], 2, vec![0, 1], 2).unwrap();
+    let b = Dataset::from_raw(vec![0.0; 3], 3, vec![0], 2).unwrap();
+    assert!(matches!(
+        a.concat(&b),
+        Err(DatasetError::DataSize {
+            expected: 2,
+            actual: 3
+        })
+    ));
+    assert!(matches!(
+        a.with_observations(Array2::<f64>::zeros((3, 2))),
+        Err(DatasetError::ObservationCount {
+            expected: 2,
+            actual: 3
+        })
+    ));
+    assert!(matches!(
+        a.clone().with_class_names(&["zero"]),
+        Err(DatasetError::TargetSize {
+            expected: 2,
+            actual: 1
+        })
+    ));
+
+    // an empty dataset still has its number of classes
+    let empty = Dataset::from_raw(Vec::<f64>::new(), 2, vec![], 10).unwrap();
+    let letters = Dataset::from_raw(vec![0.0; 2], 2, vec![25], 26).unwrap();
+    assert!(matches!(
+        empty.concat(&letters),
+        Err(DatasetError::TargetSize {
+            expected: 10,
+            actual: 26
+        })
+    ));
+    assert!(matches!(
+        letters.concat(&empty),
+        Err(DatasetError::TargetSize { .. })
+    ));
+
+    let digits = a.clone().with_class_names(&["zero", "one"]).unwrap();
+    let letters = a.with_class_names(&["a", "b"]).unwrap();
+    assert!(matches!(
+        digits.concat(&letters),
+        Err(DatasetError::ClassNames { .. })
+    ));
+}
+
+#[test]
+fn concat_keeps_the_more_precise_type() {
+    let a = Dataset::from_raw(vec![0u8, 255], 2, vec![1], 2)
+        .unwrap()
+        .with_class_names(&["zero", "one"])
+        .unwrap();
+    let b = Dataset::from_raw(vec![0.5, 1.5, 2.5, 3.5], 2, vec![0, 1], 2).unwrap();
+
+    let merged = a.concat(&b).unwrap();
+    assert_eq!(merged.observation_type(), ObservationType::F64);
+    assert_eq!(
+        merged.observations().to_owned(),
+        array![[0.0, 255.0], [0.5, 1.5], [2.5, 3.5]]
+    );
+    assert_eq!(merged.labels(), array![1, 0, 1]);
+    assert_eq!(merged.targets().row(0), array![0.0, 1.0]);
+    assert_eq!(merged.class_names(), ["zero", "one"]);
+}
+
+#[test]
+fn push_grows_the_dataset() {
+    let mut dataset = Dataset::from_raw(Vec::<u8>::new(), 0, Vec::new(), 3).unwrap();
+
+    dataset.push(&[0.4, 254.6, 300.0], 2).unwrap();
+    dataset.push(&[1.0, 2.0, 3.0], 0).unwrap();
+    assert!(matches!(
+        dataset.push(&[1.0, 2.0], 0),
+        Err(DatasetError::DataSize {
+            expected: 3,
+            actual: 2
+        })
+    ));
+    assert!(matches!(
+        dataset.push(&[1.0, 2.0, 3.0], 3),
+        Err(DatasetError::Label { .. })
+    ));
+
+    assert_eq!(
+        dataset.observations().to_owned(),
+        array![[0.0, 255.0, 255.0], [1.0, 2.0, 3.0]]
+    );
+    assert_eq!(dataset.targets(), array![[0.0, 0.0, 1.0], [1.0, 0.0, 0.0]]);
+    assert_eq!(dataset.labels(), array![2, 0]);
+}
@@ -0,0 +1,64 @@
+use mnist::dataset::Dataset;
+use mnist::models::{Activation, Model, MultiLayerPerceptron};
+use ndarray::{array, Array1, Array2};
+
+/// XOR of the two inputs, which a single layer can't learn
+fn xor(n_copies: usize) -> Dataset {
+    let observations = Array2::from_shape_fn((4 * n_copies, 2), |(i, j)| ((i % 4) >> j & 1) as f64);
+    let labels = Array1::from_shape_fn(4 * n_copies, |i| (i % 4 == 1 || i % 4 == 2) as usize);
+    Dataset::from_labels(observations, labels, 2).unwrap()
+}
+
+#[test]
+fn learns_xor_with_each_activation() {
+    let dataset = xor(4);
+    for activation in [Activation::Relu, Activation::Sigmoid, Activation::Tanh] {
+        let mut model = MultiLayerPerceptron::new(0.5, 2, vec![8], 2, activation, 0);
+        let initial_loss = model.loss(&dataset);
+        for epoch in 0..500 {
+            for batch in dataset.batches(4, true, false, epoch) {
+                model.step_batch(&batch);
+            }
+        }
+
+        assert!(
+            model.loss(&dataset) < initial_loss / 4.0,
+            "{:?}",
+            activation
+        );
+        assert_eq!(model.evaluate(&dataset), 0.0, "{:?}", activation);
+        assert_eq!(model.predict_batch(&dataset), dataset.labels());
+    }
+}
+
+#[test]
+fn predictions_are_probabilities() {
+    let model = MultiLayerPerceptron::new(0.1, 4, vec![5, 3], 3, Activation::Relu, 1);
+    let proba = model.predict_proba_raw(vec![1.0, -2.0, 3.0, 0.5]).unwrap();
+
+    assert_eq!(proba.len(), 3);
+    assert!(proba.iter().all(|&p| p > 0.0 && p < 1.0));
+    assert!((proba.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn same_seed_same_training() {
+    let dataset = xor(8);
+    let new = || MultiLayerPerceptron::new(0.1, 2, vec![4], 2, Activation::Tanh, 7);
+    let (mut a, mut b) = (new(), new());
+    for _ in 0..5 {
+        assert_eq!(a.step(&dataset), b.step(&dataset));
+    }
+
+    let mut buf = Vec::new();
+    a.write_json(&mut buf).unwrap();
+    let restored = MultiLayerPerceptron::read_json(buf.as_slice()).unwrap();
+    assert_eq!(
+        restored.predict_proba_raw(vec![1.0, 0.0]).unwrap(),
+        b.predict_proba_raw(vec![1.0, 0.0]).unwrap()
+    );
+    assert_eq!(
+        Model::predict_proba(&restored, array![0.0, 1.0].view()).to_vec(),
+        b.predict_proba_raw(vec![0.0, 1.0]).unwrap()
+    );
+}
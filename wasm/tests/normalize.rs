@@ -0,0 +1,118 @@
+use mnist::dataset::{Dataset, ObservationType};
+use mnist::models::Perceptron;
+use mnist::preprocess::{Normalization, NormalizeError, Normalizer};
+use ndarray::{array, Array1, Array2};
+
+fn dataset(observations: Array2<f64>) -> Dataset {
+    let n = observations.nrows();
+    Dataset::new(observations, Array2::zeros((n, 2)), Array1::zeros(n)).unwrap()
+}
+
+#[test]
+fn min_max_uses_training_range() {
+    let training = dataset(array![[0.0, 10.0], [255.0, 20.0], [51.0, 15.0]]);
+    let testing = dataset(array![[102.0, 30.0]]);
+
+    let mut normalizer = Normalizer::new(Normalization::MinMax, 0.0);
+    let training = normalizer.fit_transform(&training).unwrap();
+    let testing = normalizer.transform(&testing).unwrap();
+
+    assert_eq!(
+        training.observations(),
+        array![[0.0, 0.0], [1.0, 1.0], [0.2, 0.5]]
+    );
+    assert_eq!(testing.observations(), array![[0.4, 2.0]]);
+}
+
+#[test]
+fn min_max_global_uses_one_range_for_every_feature() {
+    let training = dataset(array![[0.0, 10.0, 0.0], [255.0, 20.0, 0.0]]);
+
+    let mut normalizer = Normalizer::new(Normalization::MinMaxGlobal, 0.0);
+    let training = normalizer.fit_transform(&training).unwrap();
+
+    // the constant feature is scaled like the others, not passed through raw
+    assert_eq!(
+        normalizer
+            .transform_observations(&array![[255.0, 51.0, 255.0]])
+            .unwrap(),
+        array![[1.0, 0.2, 1.0]]
+    );
+    assert_eq!(
+        training.observations(),
+        array![[0.0, 10.0 / 255.0, 0.0], [1.0, 20.0 / 255.0, 0.0]]
+    );
+}
+
+#[test]
+fn z_score_has_zero_mean_and_unit_std() {
+    let training = dataset(array![[1.0, 5.0], [3.0, 5.0]]);
+
+    let mut normalizer = Normalizer::new(Normalization::ZScore, 0.0);
+    let training = normalizer.fit_transform(&training).unwrap();
+
+    // a constant feature is only centred
+    assert_eq!(training.observations(), array![[-1.0, 0.0], [1.0, 0.0]]);
+}
+
+#[test]
+fn l2_and_binarize_need_no_fitting() {
+    let l2 = Normalizer::new(Normalization::L2, 0.0);
+    assert_eq!(
+        l2.transform_observations(&array![[3.0, 4.0], [0.0, 0.0]])
+            .unwrap(),
+        array![[0.6, 0.8], [0.0, 0.0]]
+    );
+
+    let binarize = Normalizer::new(Normalization::Binarize, 127.0);
+    let binarized = binarize
+        .transform(&dataset(array![[0.0, 127.0, 128.0, 255.0]]))
+        .unwrap();
+    assert_eq!(binarized.observation_type(), ObservationType::U8);
+    assert_eq!(binarized.observations(), array![[0.0, 0.0, 1.0, 1.0]]);
+}
+
+#[test]
+fn unfitted_or_mismatched_normalizer_is_an_error() {
+    let observations = dataset(array![[0.0, 10.0]]);
+
+    let z_score = Normalizer::new(Normalization::ZScore, 0.0);
+    assert!(matches!(
+        z_score.transform(&observations),
+        Err(NormalizeError::NotFitted)
+    ));
+
+    let mut min_max = Normalizer::new(Normalization::MinMax, 0.0);
+    min_max.fit(&dataset(array![[0.0, 1.0, 2.0]]));
+    assert!(matches!(
+        min_max.transform(&observations),
+        Err(NormalizeError::DataSize {
+            expected: 3,
+            actual: 2
+        })
+    ));
+
+    let mut perceptron = Perceptron::new(0.1, 2, 2);
+    perceptron.set_normalizer(&z_score);
+    assert!(perceptron.predict_proba_raw(vec![0.0, 10.0]).is_err());
+}
+
+#[test]
+fn normalizer_is_saved_with_the_model() {
+    let mut normalizer = Normalizer::new(Normalization::MinMax, 0.0);
+    normalizer.fit(&dataset(array![[0.0, 0.0], [255.0, 255.0]]));
+
+    let mut buf = Vec::new();
+    normalizer.write_json(&mut buf).unwrap();
+    assert_eq!(Normalizer::read_json(buf.as_slice()).unwrap(), normalizer);
+
+    let mut perceptron = Perceptron::new(0.1, 2, 2);
+    perceptron.set_normalizer(&normalizer);
+    let mut buf = Vec::new();
+    perceptron.write_json(&mut buf).unwrap();
+    let restored = Perceptron::read_json(buf.as_slice()).unwrap();
+    assert_eq!(
+        restored.predict_proba_raw(vec![255.0, 0.0]).unwrap(),
+        perceptron.predict_proba_raw(vec![255.0, 0.0]).unwrap()
+    );
+}
@@ -0,0 +1,145 @@
+use mnist::dataset::Dataset;
+use mnist::preprocess::{Pca, PcaError};
+use ndarray::{array, Array1, Array2, Axis};
+use ndarray_rand::rand::{rngs::StdRng, SeedableRng};
+use ndarray_rand::{rand_distr::Uniform, RandomExt};
+
+fn dataset(observations: Array2<f64>) -> Dataset {
+    let n = observations.nrows();
+    Dataset::new(observations, Array2::zeros((n, 1)), Array1::zeros(n)).unwrap()
+}
+
+fn assert_close(a: &Array2<f64>, b: &Array2<f64>) {
+    assert_eq!(a.dim(), b.dim());
+    assert!(
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9),
+        "{} != {}",
+        a,
+        b
+    );
+}
+
+#[test]
+fn components_follow_the_largest_variance() {
+    let observations = array![
+        [3.0, 0.0, 0.0],
+        [-3.0, 0.0, 0.0],
+        [0.0, 2.0, 0.0],
+        [0.0, -2.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [0.0, 0.0, -1.0],
+    ] + 10.0;
+
+    let mut pca = Pca::new(2);
+    let transformed = pca.fit_transform(&dataset(observations)).unwrap();
+
+    assert_eq!(transformed.data_size(), 2);
+    assert_close(pca.components(), &array![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+    let ratio = pca.explained_variance_ratio();
+    assert!((ratio[0] - 9.0 / 14.0).abs() < 1e-12);
+    assert!((ratio[1] - 4.0 / 14.0).abs() < 1e-12);
+    assert_eq!(
+        pca.transform_observations(&array![[13.0, 8.0, 10.0]])
+            .unwrap(),
+        array![[3.0, -2.0]]
+    );
+}
+
+#[test]
+fn explained_variance_selects_fewest_components() {
+    // along (1, 2, 0) with a little noise on the last feature
+    let observations = Array2::from_shape_fn((20, 3), |(i, j)| match j {
+        0 => i as f64,
+        1 => 2.0 * i as f64,
+        _ => 0.1 * (i % 2) as f64,
+    });
+
+    let mut pca = Pca::with_explained_variance(0.95);
+    pca.fit(&dataset(observations));
+    assert_eq!(pca.n_components(), 1);
+
+    assert_eq!(pca.component(1), None);
+    let direction = pca.component(0).unwrap();
+    let expected = [1.0 / 5f64.sqrt(), 2.0 / 5f64.sqrt(), 0.0];
+    assert!(direction
+        .iter()
+        .zip(expected)
+        .all(|(a, b)| (a - b).abs() < 1e-3));
+}
+
+#[test]
+fn transformed_features_are_uncorrelated() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mixing = Array2::random_using((12, 12), Uniform::new(-1.0, 1.0), &mut rng);
+    let observations =
+        Array2::random_using((200, 12), Uniform::new(0.0, 1.0), &mut rng).dot(&mixing);
+    let dataset = dataset(observations);
+
+    let mut pca = Pca::new(12);
+    let transformed = pca
+        .fit_transform(&dataset)
+        .unwrap()
+        .observations()
+        .into_owned();
+
+    // covariance of the projections is diagonal with the explained variance
+    let n = transformed.nrows() as f64;
+    let covariance = transformed.t().dot(&transformed) / n;
+    let total: f64 = dataset.observations().var_axis(Axis(0), 0.0).sum();
+    let expected = Array2::from_diag(&(Array1::from(pca.explained_variance_ratio()) * total));
+    assert_close(&covariance, &expected);
+
+    // components are orthonormal and sorted
+    assert_close(
+        &pca.components().dot(&pca.components().t()),
+        &Array2::eye(12),
+    );
+    let ratio = pca.explained_variance_ratio();
+    assert!(ratio.windows(2).all(|w| w[0] >= w[1]));
+
+    // keeping every component loses nothing
+    assert_close(
+        &pca.inverse_transform(&pca.transform(&dataset).unwrap())
+            .unwrap()
+            .observations()
+            .into_owned(),
+        &dataset.observations().into_owned(),
+    );
+}
+
+#[test]
+fn json_round_trip() {
+    let mut pca = Pca::new(1);
+    pca.fit(&dataset(array![[0.0, 1.0], [2.0, 3.0], [4.0, 4.0]]));
+
+    let mut buf = Vec::new();
+    pca.write_json(&mut buf).unwrap();
+    assert_eq!(Pca::read_json(buf.as_slice()).unwrap(), pca);
+}
+
+#[test]
+fn unfitted_or_mismatched_pca_is_an_error() {
+    let observations = dataset(array![[0.0, 1.0], [2.0, 3.0], [4.0, 4.0]]);
+
+    let mut pca = Pca::new(1);
+    assert!(matches!(
+        pca.transform(&observations),
+        Err(PcaError::NotFitted)
+    ));
+
+    pca.fit(&observations);
+    assert!(matches!(
+        pca.transform_observations(&array![[0.0, 1.0, 2.0]]),
+        Err(PcaError::DataSize {
+            expected: 2,
+            actual: 3
+        })
+    ));
+    assert!(matches!(
+        pca.inverse_transform(&observations),
+        Err(PcaError::DataSize {
+            expected: 1,
+            actual: 2
+        })
+    ));
+}
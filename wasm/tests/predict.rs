@@ -0,0 +1,91 @@
+mod common;
+
+use common::clusters;
+use mnist::dataset::Dataset;
+use mnist::models::{KMeans, KNearestNeighbors, Model, Perceptron};
+use ndarray::array;
+
+/// Probabilities sum to 1 and predict_batch agrees with predict
+fn check_predictions(model: &dyn Model, dataset: &Dataset) {
+    let batch = model.predict_batch(dataset);
+    assert_eq!(batch.len(), dataset.n_observations());
+
+    for (i, observation) in dataset.observation_iter().enumerate() {
+        let proba = model.predict_proba(observation.view());
+        assert_eq!(proba.len(), dataset.target_size());
+        assert!((proba.sum() - 1.0).abs() < 1e-12);
+        assert_eq!(model.predict(observation.view()), batch[i]);
+    }
+}
+
+#[test]
+fn knn_predicts_neighbour_labels() {
+    let dataset = clusters(20, 100.0);
+    let mut model = KNearestNeighbors::new(3);
+    model.step(&dataset);
+
+    check_predictions(&model, &dataset);
+    assert_eq!(model.predict_batch(&dataset), dataset.labels());
+    assert_eq!(
+        Model::predict_proba(&model, array![100.0, 100.0].view()),
+        array![0.0, 1.0]
+    );
+}
+
+#[test]
+fn kmeans_predicts_cluster_labels() {
+    let dataset = clusters(20, 100.0);
+    let mut model = KMeans::new(2, dataset.data_size());
+    for _ in 0..10 {
+        model.step(&dataset);
+    }
+
+    check_predictions(&model, &dataset);
+    let error = model.evaluate(&dataset);
+    let batch = model.predict_batch(&dataset);
+    let n_wrong = batch
+        .iter()
+        .zip(dataset.labels())
+        .filter(|(p, l)| p != l)
+        .count();
+    assert_eq!(n_wrong as f64 / dataset.n_observations() as f64, error);
+}
+
+#[test]
+fn kmeans_evaluate_matches_predictions_on_ties_and_empty_clusters() {
+    // one cluster with as many observations of label 0 as of label 1
+    let observations = array![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0], [3.0, 3.0]];
+    let training = Dataset::from_labels(observations, array![1, 0, 1, 0], 2).unwrap();
+    let mut model = KMeans::new(1, 2);
+    model.step(&training);
+
+    // the first of the tied labels is predicted
+    let zeros = Dataset::from_labels(array![[0.0, 0.0], [3.0, 3.0]], array![0, 0], 2).unwrap();
+    assert_eq!(model.predict_batch(&zeros), array![0, 0]);
+    assert_eq!(model.evaluate(&zeros), 0.0);
+
+    // clusters without observations predict 0 as well, which is wrong here
+    let untrained = KMeans::new(2, 2);
+    let ones = Dataset::from_labels(array![[0.0, 0.0], [3.0, 3.0]], array![1, 1], 10).unwrap();
+    check_predictions(&untrained, &ones);
+    assert_eq!(untrained.predict_batch(&ones), array![0, 0]);
+    assert_eq!(untrained.evaluate(&ones), 1.0);
+}
+
+#[test]
+fn perceptron_predicts_after_training() {
+    let dataset = clusters(20, 100.0);
+    let mut model = Perceptron::new(0.01, dataset.data_size(), dataset.target_size());
+    for _ in 0..1000 {
+        model.step(&dataset);
+    }
+
+    check_predictions(&model, &dataset);
+    assert_eq!(model.predict_batch(&dataset), dataset.labels());
+    // every output fires with zero weights
+    let untrained = Perceptron::new(0.01, 2, 4);
+    assert_eq!(
+        Model::predict_proba(&untrained, array![-1.0, -1.0].view()),
+        array![0.25, 0.25, 0.25, 0.25]
+    );
+}
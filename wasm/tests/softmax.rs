@@ -0,0 +1,62 @@
+mod common;
+
+use common::clusters;
+use mnist::dataset::Dataset;
+use mnist::models::{Model, SoftmaxRegression};
+use ndarray::{array, Array1, Array2};
+
+#[test]
+fn learns_separable_classes() {
+    let dataset = clusters(20, 1.0);
+    let mut model = SoftmaxRegression::new(0.5, 0.0, 2, 2);
+    let initial_loss = model.loss(&dataset);
+    for _ in 0..200 {
+        model.step(&dataset);
+    }
+
+    assert!(model.loss(&dataset) < initial_loss / 4.0);
+    assert_eq!(model.evaluate(&dataset), 0.0);
+    assert_eq!(model.predict_batch(&dataset), dataset.labels());
+
+    let proba = model.predict_proba_raw(vec![1.0, 1.0]).unwrap();
+    assert!((proba.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    assert!(proba[1] > 0.9);
+}
+
+#[test]
+fn probabilities_match_label_frequencies() {
+    // identical observations labelled 0 seven times out of ten
+    let labels = Array1::from_shape_fn(10, |i| (i >= 7) as usize);
+    let dataset = Dataset::from_labels(Array2::<f64>::ones((10, 1)), labels, 2).unwrap();
+    let mut model = SoftmaxRegression::new(1.0, 0.0, 1, 2);
+    for _ in 0..500 {
+        model.step(&dataset);
+    }
+
+    let proba = Model::predict_proba(&model, array![1.0].view());
+    assert!((proba[0] - 0.7).abs() < 1e-6);
+    assert!((proba[1] - 0.3).abs() < 1e-6);
+}
+
+#[test]
+fn l2_makes_predictions_less_confident() {
+    let dataset = clusters(20, 1.0);
+    let mut plain = SoftmaxRegression::new(0.5, 0.0, 2, 2);
+    let mut regularized = SoftmaxRegression::new(0.5, 1.0, 2, 2);
+    for _ in 0..200 {
+        plain.step(&dataset);
+        regularized.step(&dataset);
+    }
+
+    let observation = vec![1.0, 1.0];
+    assert!(
+        regularized.predict_proba_raw(observation.clone()).unwrap()[1]
+            < plain.predict_proba_raw(observation).unwrap()[1]
+    );
+    assert_eq!(regularized.evaluate(&dataset), 0.0);
+
+    let mut buf = Vec::new();
+    regularized.write_json(&mut buf).unwrap();
+    let restored = SoftmaxRegression::read_json(buf.as_slice()).unwrap();
+    assert_eq!(restored.loss(&dataset), regularized.loss(&dataset));
+}
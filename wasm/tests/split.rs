@@ -0,0 +1,76 @@
+mod common;
+
+use common::{ids, numbered};
+use mnist::dataset::{Dataset, DatasetError};
+use ndarray::{array, Array2};
+
+#[test]
+fn select_take_and_filter() {
+    let dataset = numbered(100);
+
+    assert_eq!(
+        ids(&dataset.select(&[5, 2, 9]).observations()),
+        vec![5, 2, 9]
+    );
+    assert_eq!(ids(&dataset.take(3).observations()), vec![0, 1, 2]);
+
+    let filtered = dataset.filter_labels(&[3, 7]);
+    assert_eq!(filtered.n_observations(), 20);
+    assert!(filtered.labels().iter().all(|&l| l == 3 || l == 7));
+    assert_eq!(filtered.targets()[(0, 3)], 1.0);
+}
+
+#[test]
+fn shuffle_is_seeded_permutation() {
+    let dataset = numbered(100);
+    let a = ids(&dataset.shuffle(1).observations());
+
+    assert_eq!(a, ids(&dataset.shuffle(1).observations()));
+    assert_ne!(a, ids(&dataset.shuffle(2).observations()));
+    let mut sorted = a.clone();
+    sorted.sort();
+    assert_eq!(sorted, ids(&dataset.observations()));
+}
+
+#[test]
+fn split_keeps_every_observation_once() {
+    let dataset = numbered(100);
+    let (training, validation) = dataset.split(0.8, 0);
+
+    assert_eq!(training.n_observations(), 80);
+    assert_eq!(validation.n_observations(), 20);
+    let mut all = [
+        ids(&training.observations()),
+        ids(&validation.observations()),
+    ]
+    .concat();
+    all.sort();
+    assert_eq!(all, ids(&dataset.observations()));
+}
+
+#[test]
+fn stratified_split_keeps_class_balance() {
+    let dataset = numbered(100);
+    let (training, validation) = dataset.stratified_split(0.7, 0);
+
+    for label in 0..10 {
+        let count = |d: &Dataset| d.labels().iter().filter(|&&l| l == label).count();
+        assert_eq!(count(&training), 7);
+        assert_eq!(count(&validation), 3);
+    }
+}
+
+#[test]
+fn labels_without_a_target_are_rejected() {
+    assert!(matches!(
+        Dataset::new(
+            Array2::<f64>::zeros((2, 1)),
+            Array2::zeros((2, 3)),
+            array![0, 3]
+        ),
+        Err(DatasetError::Label {
+            label: 3,
+            n_classes: 3
+        })
+    ));
+}
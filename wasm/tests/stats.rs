@@ -0,0 +1,82 @@
+use mnist::dataset::{Dataset, DatasetError, ObservationType};
+use ndarray::{array, Array1, Array2};
+
+fn dataset() -> Dataset {
+    let observations: Array2<u8> = array![
+        [0, 0, 0, 0],
+        [255, 255, 0, 0],
+        [0, 0, 0, 0],
+        [255, 253, 2, 0],
+        [10, 20, 30, 40],
+    ];
+    let labels = array![0, 1, 0, 2, 1];
+    Dataset::from_labels(observations, labels, 4).unwrap()
+}
+
+#[test]
+fn class_counts_and_means() {
+    let dataset = dataset();
+
+    assert_eq!(dataset.class_counts(), array![2, 2, 1, 0]);
+    assert_eq!(
+        dataset.class_means(),
+        array![
+            [0.0, 0.0, 0.0, 0.0],
+            [132.5, 137.5, 15.0, 20.0],
+            [255.0, 253.0, 2.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+        ]
+    );
+}
+
+#[test]
+fn pixel_mean_and_variance() {
+    let observations = array![[0.0, 1.0], [2.0, 1.0], [4.0, 1.0]];
+    let dataset = Dataset::new(observations, Array2::zeros((3, 1)), Array1::zeros(3)).unwrap();
+
+    assert_eq!(dataset.pixel_mean(), array![2.0, 1.0]);
+    assert_eq!(dataset.pixel_variance(), array![8.0 / 3.0, 0.0]);
+}
+
+#[test]
+fn intensity_histogram_covers_every_value() {
+    let histogram = dataset().intensity_histogram(4);
+
+    assert_eq!(histogram.sum(), 20);
+    assert_eq!(histogram, array![16, 0, 0, 4]);
+}
+
+#[test]
+fn near_duplicates() {
+    let dataset = dataset();
+
+    assert_eq!(dataset.near_duplicates(0.0).unwrap(), vec![(0, 2)]);
+    // observations 1 and 3 differ by 4 in total, 1 per feature
+    assert_eq!(dataset.near_duplicates(1.0).unwrap(), vec![(0, 2), (1, 3)]);
+
+    for observation_type in [ObservationType::F32, ObservationType::F64] {
+        let converted = dataset.clone().into_observation_type(observation_type);
+        assert_eq!(
+            converted.near_duplicates(1.0).unwrap(),
+            vec![(0, 2), (1, 3)]
+        );
+    }
+}
+
+#[test]
+fn near_duplicates_of_large_datasets_are_exact_only() {
+    let observations = Array2::from_shape_fn((2_001, 2), |(i, j)| match j {
+        0 => (i / 256) as u8,
+        _ => (i % 256) as u8,
+    });
+    let dataset = Dataset::from_labels(observations, Array1::zeros(2_001), 1).unwrap();
+
+    assert!(matches!(
+        dataset.near_duplicates(1.0),
+        Err(DatasetError::TooManyObservations {
+            max: 2_000,
+            actual: 2_001
+        })
+    ));
+    assert_eq!(dataset.near_duplicates(0.0).unwrap(), vec![]);
+}
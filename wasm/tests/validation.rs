@@ -0,0 +1,58 @@
+mod common;
+
+use common::clusters;
+use mnist::models::KNearestNeighbors;
+use mnist::validation::{cross_validate, stratified_folds, CrossValidation, ValidationError};
+
+#[test]
+fn folds_are_stratified_partition() {
+    let dataset = clusters(50, 100.0);
+    let folds = stratified_folds(&dataset, 5, 0).unwrap();
+
+    let mut all: Vec<usize> = folds.concat();
+    all.sort();
+    assert_eq!(all, (0..50).collect::<Vec<_>>());
+    for fold in &folds {
+        assert_eq!(fold.len(), 10);
+        assert_eq!(fold.iter().filter(|&&i| i % 2 == 0).count(), 5);
+    }
+}
+
+#[test]
+fn cross_validate_knn() {
+    let dataset = clusters(50, 100.0);
+    let result = cross_validate(&dataset, 5, 0, 1, || KNearestNeighbors::new(3)).unwrap();
+
+    assert_eq!(result.fold_errors, vec![0.0; 5]);
+    assert_eq!(result.mean(), 0.0);
+    assert_eq!(result.std_dev(), 0.0);
+}
+
+#[test]
+fn mean_and_std_dev() {
+    let result = CrossValidation {
+        fold_errors: vec![0.1, 0.2, 0.3],
+    };
+
+    assert!((result.mean() - 0.2).abs() < 1e-12);
+    assert!((result.std_dev() - 0.1).abs() < 1e-12);
+}
+
+#[test]
+fn more_folds_than_observations_are_rejected() {
+    let dataset = clusters(4, 100.0);
+
+    assert!(matches!(
+        cross_validate(&dataset, 5, 0, 1, || KNearestNeighbors::new(1)),
+        Err(ValidationError::Folds {
+            k: 5,
+            n_observations: 4
+        })
+    ));
+    assert!(matches!(
+        stratified_folds(&dataset, 1, 0),
+        Err(ValidationError::Folds { k: 1, .. })
+    ));
+    let folds = stratified_folds(&dataset, 4, 0).unwrap();
+    assert!(folds.iter().all(|fold| fold.len() == 1));
+}
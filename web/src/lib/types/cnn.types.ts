@@ -0,0 +1,10 @@
+interface ConvolutionalNeuralNetworkParam {
+    learning_rate: number;
+    max_iter: number;
+    batch_size: number;
+    filters: number[];
+    hidden_layers: number[];
+    activation: "relu" | "sigmoid" | "tanh";
+}
+
+type ConvolutionalNeuralNetworkType = "cnn";
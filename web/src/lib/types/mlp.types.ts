@@ -0,0 +1,9 @@
+interface MultiLayerPerceptronParam {
+    learning_rate: number;
+    max_iter: number;
+    batch_size: number;
+    hidden_layers: number[];
+    activation: "relu" | "sigmoid" | "tanh";
+}
+
+type MultiLayerPerceptronType = "mlp";
@@ -1,9 +1,18 @@
-type ModelTypes = KMeansType | KNearestNeighborsType | PerceptronType;
+type ModelTypes =
+    | KMeansType
+    | KNearestNeighborsType
+    | PerceptronType
+    | MultiLayerPerceptronType
+    | ConvolutionalNeuralNetworkType
+    | SoftmaxRegressionType;
 
 interface ModelParameters {
     kmeans: KMeansParam;
     knn: KNearestNeighborsParam;
     perceptron: PerceptronParam;
+    mlp: MultiLayerPerceptronParam;
+    cnn: ConvolutionalNeuralNetworkParam;
+    softmax: SoftmaxRegressionParam;
 }
 
 type ModelParametersUnion = {
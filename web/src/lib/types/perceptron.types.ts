@@ -1,6 +1,7 @@
 interface PerceptronParam {
     learning_rate: number;
     max_iter: number;
+    batch_size: number;
 }
 
 type PerceptronType = "perceptron";
@@ -0,0 +1,8 @@
+interface SoftmaxRegressionParam {
+    learning_rate: number;
+    l2: number;
+    max_iter: number;
+    batch_size: number;
+}
+
+type SoftmaxRegressionType = "softmax";
@@ -5,7 +5,8 @@ export interface ReqMsg {
     start_training: null;
     stop_training: null;
     evaluate: null;
-    predict: Float64Array;
+    /** RGBA pixels of the canvas */
+    predict: { rgba: Uint8ClampedArray; width: number; height: number };
     // from worker
     step: { i: number; err: number };
 }
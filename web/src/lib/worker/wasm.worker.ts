@@ -1,27 +1,18 @@
 import init, {
     set_panic_hook,
+    rgba_to_observation,
+    AnyModel,
+    Batches,
     Dataset,
     MNIST,
-    KMeans,
-    KNearestNeighbors,
-    Perceptron,
-    TemplateModel,
+    Normalization,
+    Normalizer,
 } from "@wasm/mnist";
 import { type AckMsg, type ReqMsg } from "./wasm.types";
 import { Pipe } from "./wasm.pipe";
 
-function create_new_model({ type, param }: ModelParametersUnion) {
-    switch (type) {
-        case "kmeans":
-            return new KMeans(param.n_clusters, 28 * 28);
-        case "knn":
-            return new KNearestNeighbors(param.k);
-        case "perceptron":
-            return new Perceptron(param.learning_rate, 28 * 28, 10);
-        default:
-            return undefined;
-    }
-}
+/** Models trained on pixels scaled to 0..=1 instead of 0..=255 */
+const NORMALIZED_MODELS = ["mlp", "cnn", "softmax"];
 
 class WasmState {
     pipe: Pipe<typeof self, ReqMsg, AckMsg> = new Pipe(self);
@@ -30,7 +21,13 @@ class WasmState {
         testing: Dataset;
     };
 
-    model: TemplateModel | undefined = undefined;
+    model: AnyModel | undefined = undefined;
+    /** Train on mini-batches instead of the full training set when set */
+    batches: Batches | undefined = undefined;
+    /** Fitted on the training set for models in NORMALIZED_MODELS, one range for all pixels */
+    normalizer: Normalizer | undefined = undefined;
+    /** Testing set transformed by the normalizer */
+    testing: Dataset | undefined = undefined;
     timer: number | undefined = undefined;
     iteration: number = 0;
 
@@ -72,7 +69,34 @@ class WasmState {
 
     init_model(data: ModelParametersUnion) {
         if (this.model == undefined) {
-            this.model = create_new_model(data);
+            // a normalized copy of the whole training set would be stored as
+            // f64, so these models only train on normalized mini-batches
+            if (NORMALIZED_MODELS.includes(data.type) && !("batch_size" in data.param)) {
+                console.error(`[worker][init_model]: ${data.type} requires a batch_size`);
+                return;
+            }
+            try {
+                this.model = new AnyModel(data.type, JSON.stringify(data.param));
+            } catch (e) {
+                console.error(`[worker][init_model]: ${e}`);
+                return;
+            }
+            if (NORMALIZED_MODELS.includes(data.type)) {
+                this.normalizer = new Normalizer(Normalization.MinMaxGlobal, 0);
+                this.normalizer.fit(this.dataset.training);
+                // predict normalizes the drawn digits the same way
+                this.model.set_normalizer(this.normalizer);
+                this.testing = this.normalizer.transform(this.dataset.testing);
+            }
+            if ("batch_size" in data.param) {
+                this.batches = new Batches(
+                    this.dataset.training.n_observations,
+                    data.param.batch_size,
+                    true,
+                    false,
+                    BigInt(0)
+                );
+            }
         }
     }
 
@@ -84,6 +108,18 @@ class WasmState {
             this.model.free();
             this.model = undefined;
         }
+        if (this.batches != undefined) {
+            this.batches.free();
+            this.batches = undefined;
+        }
+        if (this.normalizer != undefined) {
+            this.normalizer.free();
+            this.normalizer = undefined;
+        }
+        if (this.testing != undefined) {
+            this.testing.free();
+            this.testing = undefined;
+        }
     }
 
     // can only start if model is created and no timer is currently running
@@ -96,10 +132,15 @@ class WasmState {
         this.timer = undefined;
     }
 
-    predict(data: Float64Array) {
+    predict({ rgba, width, height }: ReqMsg["predict"]) {
         let prediction = new Float64Array();
         if (this.model) {
-            prediction = this.model.predict(data);
+            const observation = rgba_to_observation(
+                new Uint8Array(rgba.buffer),
+                width,
+                height
+            );
+            prediction = this.model.predict_proba(observation);
         }
         return {
             value: prediction,
@@ -110,7 +151,7 @@ class WasmState {
     evaluate() {
         let value = NaN;
         if (this.model) {
-            value = this.model.evaluate(this.dataset.testing);
+            value = this.model.evaluate(this.testing ?? this.dataset.testing);
         }
         return { value }
     }
@@ -118,7 +159,19 @@ class WasmState {
     /** Hardcoded maximum of 1,000,000 steps */
     private step() {
         if (this.model && this.iteration <= 1_000_000) {
-            const err = this.model.step(this.dataset.training);
+            let err;
+            if (this.batches) {
+                let batch = this.batches.next_batch(this.dataset.training);
+                if (this.normalizer) {
+                    const normalized = this.normalizer.transform(batch);
+                    batch.free();
+                    batch = normalized;
+                }
+                err = this.model.step(batch);
+                batch.free();
+            } else {
+                err = this.model.step(this.dataset.training);
+            }
             this.iteration++;
             this.pipe.request("step", { i: this.iteration, err });
             this.timer = setTimeout(this.step.bind(this), 0);